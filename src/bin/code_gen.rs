@@ -1,33 +1,95 @@
-use std::fs::File;
-use std::io::Write;
-
 use db_engine::{
-    db_class::DbClass, db_field::DbClassLinkMultiple as LnM, db_field::DbClassLinkSingle as LnS,
-    db_field::DbClassSimpleField as SF, db_manager::DbManager,
+    build, db_class::DbClass, db_class::DbStruct, db_field::DbClassLinkMultiple as LnM,
+    db_field::DbClassLinkSingle as LnS, db_field::DbClassSimpleField as SF,
+    db_field::DbClassVectorField as VF, db_field::DbFieldDefault, db_field::DbFieldRule,
+    db_field::DbFieldType, db_field::DbVectorDistance, db_manager::DbManager, db_object::DbObject,
+    db_value_enum::DbValueEnum,
 };
 
 fn main() {
     let mut mng = DbManager::new();
+    mng.add_derive(DbStruct::Id, "PartialEq");
+    mng.add_derive(DbStruct::Id, "Eq");
+    mng.add_derive(DbStruct::Id, "Hash");
+    let status = mng.add_value_enum(
+        DbValueEnum::with_name("Status")
+            .with_doc("Account state of a user.")
+            .add_variant_with_value("Active", "active")
+            .add_variant_with_value("Suspended", "suspended"),
+    );
+    let address = mng.add_object(
+        DbObject::with_name("Address")
+            .with_doc("Postal address.")
+            .add_field(SF::new("street", DbFieldType::String))
+            .add_field(SF::new("city", DbFieldType::String)),
+    );
     let person = mng.add_class(
         DbClass::with_name("Person")
-            .add_field(SF::new("name", "String"))
-            .add_field(SF::new("age", "u16")),
+            .with_doc("A person known to the system.")
+            .with_derive(DbStruct::Main, "PartialEq")
+            .add_field(SF::with_rules(
+                "name",
+                DbFieldType::String,
+                vec![DbFieldRule::MinLength(1), DbFieldRule::MaxLength(100)],
+            ))
+            .add_field(
+                SF::with_rules(
+                    "age",
+                    DbFieldType::U16,
+                    vec![DbFieldRule::Max("150".to_string())],
+                )
+                .with_doc("Age in years."),
+            )
+            .add_field(SF::with_default(
+                "active",
+                DbFieldType::Bool,
+                DbFieldDefault::Literal("true".to_string()),
+            ))
+            .with_db_asserts()
+            .with_history(),
+    );
+    let user = mng.add_class(
+        DbClass::with_name("User")
+            .add_field(SF::with_rules(
+                "email",
+                DbFieldType::String,
+                vec![DbFieldRule::Regex("^[^@]+@[^@]+$".to_string())],
+            ))
+            .add_field(SF::new(
+                "address",
+                DbFieldType::option(DbFieldType::embedded(&address)),
+            ))
+            .add_field(SF::new("status", DbFieldType::value_enum(&status)))
+            .with_soft_delete(),
+    );
+    let guest =
+        mng.add_class(DbClass::with_name("Guest").add_field(SF::new("nick", DbFieldType::String)));
+    let car = mng.add_class(
+        DbClass::with_name("Car")
+            .add_field(SF::new("use", DbFieldType::String))
+            .add_field(LnS::new("owner", &person).with_doc("Registered owner of the car."))
+            .add_field(VF::new("embedding", 4, DbVectorDistance::Euclidean)),
+    );
+    let _garage = mng.add_class(
+        DbClass::with_name("Garage")
+            .add_field(LnM::new_prefetch("cars", &car))
+            .add_field(SF::computed(
+                "car_count",
+                DbFieldType::Usize,
+                "array::len(cars)",
+            ))
+            .with_timestamps()
+            .with_version(),
+    );
+    mng.add_enum_with_doc(
+        "PersonEnum",
+        "Any kind of person.",
+        &person,
+        vec![&user, &guest],
     );
-    let user = mng.add_class(DbClass::with_name("User").add_field(SF::new("email", "String")));
-    let guest = mng.add_class(DbClass::with_name("Guest").add_field(SF::new("nick", "String")));
-    let car = mng.add_class(DbClass::with_name("Car").add_field(LnS::new("owner", &person)));
-    let _garage =
-        mng.add_class(DbClass::with_name("Garage").add_field(LnM::new_prefetch("cars", &car)));
-    mng.add_enum("PersonEnum", &person, vec![&user, &guest]);
     mng.add_extension(&person, "PersonEnum", &user);
     mng.add_extension(&person, "PersonEnum", &guest);
 
-    let tokens = mng.to_tokens();
-    let code = prettyplease::unparse(&syn::parse2(tokens).unwrap());
-    let path = "src/bin/generated/types.rs";
-    File::create(path)
-        .unwrap()
-        .write_all(code.as_bytes())
-        .unwrap();
-    println!("Written to file: {}", path);
+    let path = build::generate_modules(mng, "src/bin/generated/types");
+    println!("Written to directory: {}", path.display());
 }
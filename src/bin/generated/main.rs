@@ -1,22 +1,27 @@
+#[allow(dead_code)]
 mod types;
 
-use surrealdb::engine::remote::ws::Ws;
+use db_engine::runtime::v1::{DbError, DbExtend, DbLink, DbListQuery, DbResult, Repository};
+use futures::TryStreamExt;
+use surrealdb::engine::any::{self, Any};
 use surrealdb::opt::auth::Root;
 use surrealdb::Surreal;
-use types::ValueUser;
+use types::{UserId, ValueUser};
 
 use crate::types::{
-    DbExtend, DbLink, PersonEnumBase, ValueCar, ValueGarage, ValueGuest, ValuePerson,
+    db_define, Address, Car, Person, PersonEnum, PersonEnumBase, PersonEnumOrderBy, PersonGroupBy,
+    PersonMetric, PersonOrderBy, Status, ValueCar, ValueGarage, ValueGuest, ValuePerson,
 };
 
 #[tokio::main]
-async fn main() -> surrealdb::Result<()> {
+#[allow(clippy::result_large_err)]
+async fn main() -> DbResult<()> {
     let db_username = std::env::var("DB_USERNAME").unwrap();
     let db_password = std::env::var("DB_PASSWORD").unwrap();
     let db_ws = std::env::var("DB_WS").unwrap();
 
     // Connect to the server
-    let db = Surreal::new::<Ws>(db_ws).await?;
+    let db = any::connect(format!("ws://{}", db_ws)).await?;
 
     // Signin as a namespace, database, or root user
     db.signin(Root {
@@ -28,23 +33,37 @@ async fn main() -> surrealdb::Result<()> {
 
     // Select a specific namespace / database
     db.use_ns("test").use_db("test").await?;
+    db_define(&db).await?;
 
     let person = ValuePerson {
+        active: None,
         age: 20,
         name: "Jan Kowalski".to_string(),
     }
     .db_create(&db)
     .await?;
     dbg!(&person);
+    dbg!(person.db_history(&db).await?);
     let user = ValueUser {
-        PersonEnumBase: DbLink::Existing(person),
+        person_enum_base: DbLink::Existing(person),
         email: "test@test.pl".to_string(),
+        status: Status::Active,
+        address: Some(Address {
+            street: "Main Street 1".to_string(),
+            city: "Warsaw".to_string(),
+        }),
     }
     .db_create_get(&db)
     .await?;
-    dbg!(user);
+    dbg!(&user);
+    let user_id = UserId::from(user);
+    user_id.db_delete(&db).await?;
+    dbg!(user_id.db_get(&db).await?);
+    dbg!(user_id.with_deleted().db_get(&db).await?);
+    user_id.db_restore(&db).await?;
     let guest = ValueGuest {
-        PersonEnumBase: DbLink::New(ValuePerson {
+        person_enum_base: DbLink::New(ValuePerson {
+            active: None,
             age: 20,
             name: "Mariusz Mariuszewski".to_string(),
         }),
@@ -56,36 +75,95 @@ async fn main() -> surrealdb::Result<()> {
     let guest_person: PersonEnumBase = guest.clone().db_extend(&db).await?;
     dbg!(guest_person);
 
-    let garage = ValueGarage {
+    let mut garage = ValueGarage {
         cars: DbLink::New(vec![
             ValueCar {
-                owner: DbLink::Existing(guest.PersonEnumBase),
+                r#use: "private".to_string(),
+                owner: DbLink::Existing(guest.person_enum_base),
+                embedding: vec![0.1, 0.2, 0.3, 0.4],
             },
             ValueCar {
+                r#use: "private".to_string(),
                 owner: DbLink::Existing(
                     ValueGuest {
                         nick: "Nested creation".to_string(),
-                        PersonEnumBase: DbLink::New(ValuePerson {
+                        person_enum_base: DbLink::New(ValuePerson {
+                            active: None,
                             name: "Even more nesting".to_string(),
                             age: 18,
                         }),
                     }
                     .db_create_get(&db)
                     .await?
-                    .PersonEnumBase,
+                    .person_enum_base,
                 ),
+                embedding: vec![0.4, 0.3, 0.2, 0.1],
             },
             ValueCar {
+                r#use: "private".to_string(),
                 owner: DbLink::New(ValuePerson {
+                    active: None,
                     name: "Kacper Kacperski".to_string(),
                     age: 100,
                 }),
+                embedding: vec![0.9, 0.9, 0.9, 0.9],
             },
         ]),
     }
     .db_create_get(&db)
     .await?;
-    dbg!(garage);
+    dbg!(&garage);
+    let mut stale = garage.clone();
+    garage.db_update(&db).await?;
+    if let Err(DbError::Conflict(id)) = stale.db_update(&db).await {
+        println!("Garage {} was updated concurrently", id);
+    }
+
+    let mut cursor = None;
+    loop {
+        let page = Person::db_list(
+            &db,
+            DbListQuery::cursor(cursor, 2)
+                .order_by(PersonOrderBy::Age)
+                .with_total(),
+        )
+        .await?;
+        dbg!(&page.items, page.total);
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    let members = PersonEnum::db_list(
+        &db,
+        DbListQuery::offset(0, 10).order_by(PersonEnumOrderBy::Name),
+    )
+    .await?;
+    dbg!(members.items);
+
+    let by_activity = Person::db_aggregate()
+        .group_by(PersonGroupBy::Active)
+        .metric(PersonMetric::Age)
+        .fetch(&db)
+        .await?;
+    dbg!(by_activity);
+
+    let mut cars = Car::db_stream(&db, 10);
+    while let Some(car) = cars.try_next().await? {
+        dbg!(car);
+    }
+
+    let similar = Car::db_knn(&db, &[0.1, 0.2, 0.3, 0.5], 2).await?;
+    dbg!(similar);
+
+    dbg!(count_all::<Person>(&db).await?);
+    dbg!(count_all::<Car>(&db).await?);
 
     Ok(())
 }
+
+async fn count_all<T: Repository>(db: &Surreal<Any>) -> DbResult<Option<u64>> {
+    let page = T::list(db, DbListQuery::offset(0, 1).with_total()).await?;
+    Ok(page.total)
+}
@@ -0,0 +1,426 @@
+// @generated by db-engine. Do not edit.
+use super::*;
+#[allow(unused_imports)]
+use super::{futures, serde, serde_json, surrealdb};
+pub(super) const DB_DEFINITIONS: &[&str] = &[
+    "DEFINE TABLE `a5cdf07dbbc15892dcb64f1553ebb474330393705ce470b22f0194f582234371` SCHEMALESS;",
+    "DEFINE FIELD use ON TABLE `a5cdf07dbbc15892dcb64f1553ebb474330393705ce470b22f0194f582234371` TYPE string;",
+    "DEFINE FIELD embedding ON TABLE `a5cdf07dbbc15892dcb64f1553ebb474330393705ce470b22f0194f582234371` TYPE array<float> ASSERT array::len($value) = 4;",
+    "DEFINE FIELD owner ON TABLE `a5cdf07dbbc15892dcb64f1553ebb474330393705ce470b22f0194f582234371` TYPE record<`6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d`> COMMENT \"Registered owner of the car.\";",
+    "DEFINE INDEX embedding_index ON TABLE `a5cdf07dbbc15892dcb64f1553ebb474330393705ce470b22f0194f582234371` FIELDS embedding MTREE DIMENSION 4 DIST EUCLIDEAN;",
+];
+/// Id of a [`Car`] record.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "a5cdf07dbbc15892dcb64f1553ebb474330393705ce470b22f0194f582234371")]
+pub struct CarId {
+    #[serde(deserialize_with = "thing_to_string")]
+    pub id: String,
+}
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "a5cdf07dbbc15892dcb64f1553ebb474330393705ce470b22f0194f582234371")]
+pub struct Car {
+    #[serde(deserialize_with = "thing_to_string")]
+    pub id: String,
+    pub r#use: String,
+    pub embedding: Vec<f32>,
+    /// Registered owner of the car.
+    pub owner: PersonId,
+}
+/// Values used to create a [`Car`] record.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "a5cdf07dbbc15892dcb64f1553ebb474330393705ce470b22f0194f582234371")]
+pub struct ValueCar {
+    pub r#use: String,
+    pub embedding: Vec<f32>,
+    /// Registered owner of the car.
+    #[serde(serialize_with = "db_link_to_thing")]
+    pub owner: DbLink<PersonId, ValuePerson>,
+}
+/// Stored form of a [`Car`] record.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "a5cdf07dbbc15892dcb64f1553ebb474330393705ce470b22f0194f582234371")]
+pub struct CarSerializer {
+    pub r#use: String,
+    pub embedding: Vec<f32>,
+    /// Registered owner of the car.
+    pub owner: Thing,
+}
+impl ValueCar {
+    /// Validates the values and creates a new record, creating linked records first.
+    pub async fn db_create<B: DbBackend>(mut self, db: &B) -> DbResult<CarId> {
+        self.validate()?;
+        if let DbLink::New(n) = self.owner {
+            let result = Box::pin(n.db_create(db)).await?;
+            self.owner = DbLink::Existing(result);
+        }
+        let id = db
+            .create(CarId::class_hash(), CarId::computed_fields(), self)
+            .await?;
+        Ok(CarId { id })
+    }
+    /// Creates the record and fetches it back.
+    pub async fn db_create_get<B: DbBackend>(self, db: &B) -> DbResult<Car> {
+        Ok(self.db_create(db).await?.db_get(db).await?.unwrap())
+    }
+    /// Checks the field rules.
+    pub fn validate(&self) -> Result<(), Vec<DbValidationError>> {
+        Ok(())
+    }
+}
+impl Car {
+    /// Validates the record and stores its current values.
+    pub async fn db_update<B: DbBackend>(&self, db: &B) -> DbResult<Option<CarId>> {
+        self.validate()?;
+        let updated = db
+            .update(
+                CarId::class_hash(),
+                &self.id,
+                CarId::computed_fields(),
+                ValueCar::from(self.clone()),
+                DbGuard {
+                    active: false,
+                    version: None,
+                },
+            )
+            .await?;
+        Ok(updated.map(|u| CarId { id: u.id }))
+    }
+    /// Stores the fields present in the patch, keeping the others, and fetches the
+    /// record back. Field rules are not checked for the patch.
+    pub async fn db_merge<B: DbBackend, P: Serialize + Send + 'static>(
+        &self,
+        db: &B,
+        patch: P,
+    ) -> DbResult<Option<Car>> {
+        let Some(updated) = db
+            .merge(
+                CarId::class_hash(),
+                &self.id,
+                CarId::computed_fields(),
+                patch,
+                DbGuard {
+                    active: false,
+                    version: None,
+                },
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        CarId { id: updated.id }.db_get(db).await
+    }
+    /// Checks the field rules.
+    pub fn validate(&self) -> Result<(), Vec<DbValidationError>> {
+        Ok(())
+    }
+}
+impl CarSerializer {
+    /// Resolves the links of a stored record.
+    ///
+    /// Prefetched links include soft-deleted records. A prefetched link to a
+    /// record that no longer exists makes the record unresolvable.
+    pub async fn db_resolve<B: DbBackend>(self, id: String, _db: &B) -> DbResult<Option<Car>> {
+        let owner = PersonId {
+            id: self.owner.id.to_string(),
+        };
+        Ok(Some(Car {
+            id,
+            owner,
+            r#use: self.r#use,
+            embedding: self.embedding,
+        }))
+    }
+}
+impl Car {
+    /// Finds the records nearest to the given vector with the field's vector index.
+    pub async fn db_knn(db: &Surreal<Any>, vector: &[f32], k: usize) -> DbResult<Vec<(Car, f32)>> {
+        if vector.len() != 4usize {
+            return Err(DbError::Validation(vec![DbValidationError {
+                field: "embedding".to_string(),
+                message: "must have 4 dimensions".to_string(),
+            }]));
+        }
+        #[derive(Deserialize)]
+        #[serde(crate = "db_engine::runtime::v1::serde")]
+        struct Row {
+            #[serde(deserialize_with = "thing_to_string")]
+            id: String,
+            distance: f32,
+            #[serde(flatten)]
+            value: CarSerializer,
+        }
+        let candidates = k;
+        let rows: Vec<Row> = db
+            .query(
+                format!(
+                    "SELECT *, vector::distance::euclidean(embedding, $vector) AS distance FROM type::table($table) WHERE embedding <|{}|> $vector ORDER BY distance LIMIT {}",
+                    candidates, k
+                ),
+            )
+            .bind(("table", CarId::class_hash()))
+            .bind(("vector", vector.to_vec()))
+            .await?
+            .take(0)?;
+        let resolved = join_all(rows.into_iter().map(|row| async move {
+            let value = row.value.db_resolve(row.id, db).await?;
+            DbResult::Ok(value.map(|v| (v, row.distance)))
+        }))
+        .await;
+        Ok(resolved
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+}
+/// Fields a list can be ordered by.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum CarOrderBy {
+    #[default]
+    Id,
+    Use,
+}
+impl DbOrderBy for CarOrderBy {
+    fn field(&self) -> &'static str {
+        match self {
+            CarOrderBy::Id => "id",
+            CarOrderBy::Use => "use",
+        }
+    }
+    fn surql_type(&self) -> &'static str {
+        match self {
+            CarOrderBy::Id => "record",
+            CarOrderBy::Use => "string",
+        }
+    }
+}
+impl Car {
+    /// Fetches one page of records.
+    pub async fn db_list(db: &Surreal<Any>, query: DbListQuery<CarOrderBy>) -> DbResult<Page<Car>> {
+        let conditions = vec![];
+        let (records, has_more, total) = db_list_records::<_, DbRecord<CarSerializer>>(
+            db,
+            vec![CarId::class_hash()],
+            &query,
+            conditions,
+        )
+        .await?;
+        let next_cursor = db_next_cursor(&query, has_more, CarId::class_hash(), records.last());
+        let items = join_all(
+            records
+                .into_iter()
+                .map(|record| record.value.db_resolve(record.id, db)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        Ok(Page {
+            items,
+            next_cursor,
+            total,
+            has_more,
+        })
+    }
+    /// Streams all records, fetching them in batches. A `batch_size` of 0 is treated as 1.
+    pub fn db_stream(db: &Surreal<Any>, batch_size: u64) -> BoxStream<'_, DbResult<Car>> {
+        let batch_size = batch_size.max(1);
+        futures::stream::try_unfold(Some(None), move |cursor| async move {
+            let Some(cursor) = cursor else {
+                return DbResult::Ok(None);
+            };
+            let page = Car::db_list(db, DbListQuery::cursor(cursor, batch_size)).await?;
+            let items = futures::stream::iter(page.items.into_iter().map(Ok));
+            Ok(Some((items, page.next_cursor.map(Some))))
+        })
+        .try_flatten()
+        .boxed()
+    }
+}
+/// Fields an aggregation can be grouped by.
+#[derive(Debug, Clone, Copy)]
+pub enum CarGroupBy {
+    Use,
+}
+impl CarGroupBy {
+    fn field(&self) -> &'static str {
+        match *self {
+            CarGroupBy::Use => "use",
+        }
+    }
+}
+/// Value of the group an aggregated row belongs to.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+pub enum CarGroupKey {
+    Use(String),
+}
+/// Numeric fields an aggregation can summarize.
+#[derive(Debug, Clone, Copy)]
+pub enum CarMetric {}
+impl CarMetric {
+    fn field(&self) -> &'static str {
+        match *self {}
+    }
+}
+/// Builder for aggregation queries.
+#[derive(Debug, Clone, Default)]
+pub struct CarAggregate {
+    group_by: Option<CarGroupBy>,
+    metric: Option<CarMetric>,
+}
+impl CarAggregate {
+    /// Groups the rows by the given field.
+    pub fn group_by(mut self, group_by: CarGroupBy) -> Self {
+        self.group_by = Some(group_by);
+        self
+    }
+    /// Computes sum, average, minimum and maximum of the given field.
+    pub fn metric(mut self, metric: CarMetric) -> Self {
+        self.metric = Some(metric);
+        self
+    }
+    /// Runs the aggregation.
+    pub async fn fetch(self, db: &Surreal<Any>) -> DbResult<Vec<DbAggregate<CarGroupKey>>> {
+        let mut fields = vec!["count() AS count".to_string()];
+        if let Some(metric) = self.metric {
+            let f = metric.field();
+            fields
+                .push(
+                    format!(
+                        "math::sum({f}) AS sum, math::mean({f}) AS avg, math::min({f}) AS min, math::max({f}) AS max"
+                    ),
+                );
+        }
+        let group = match self.group_by {
+            Some(group_by) => {
+                fields.insert(0, format!("{} AS key", group_by.field()));
+                "GROUP BY key"
+            }
+            None => "GROUP ALL",
+        };
+        let sql = format!(
+            "SELECT {} FROM type::table($table){} {}",
+            fields.join(", "),
+            "",
+            group
+        );
+        let mut response = db.query(sql).bind(("table", CarId::class_hash())).await?;
+        Ok(match self.group_by {
+            None => response.take(0)?,
+            Some(CarGroupBy::Use) => response
+                .take::<Vec<DbAggregate<String>>>(0)?
+                .into_iter()
+                .map(|row| row.map_key(CarGroupKey::Use))
+                .collect(),
+        })
+    }
+}
+impl Car {
+    /// Starts an aggregation query.
+    pub fn db_aggregate() -> CarAggregate {
+        CarAggregate::default()
+    }
+}
+impl CarId {
+    /// Includes soft-deleted records in the following lookup.
+    pub fn with_deleted(&self) -> WithDeleted<CarId> {
+        WithDeleted(self.clone())
+    }
+    /// Fetches the record unless it was soft-deleted.
+    pub async fn db_get<B: DbBackend>(&self, db: &B) -> DbResult<Option<Car>> {
+        self.with_deleted().db_get(db).await
+    }
+}
+impl WithDeleted<CarId> {
+    /// Fetches the record, including a soft-deleted one.
+    pub async fn db_get<B: DbBackend>(&self, db: &B) -> DbResult<Option<Car>> {
+        let Some(deserialized): Option<CarSerializer> =
+            db.select(CarId::class_hash(), &self.0.id).await?
+        else {
+            return Ok(None);
+        };
+        deserialized.db_resolve(self.0.id.clone(), db).await
+    }
+}
+impl CarId {
+    /// Removes the record.
+    pub async fn db_delete<B: DbBackend>(&self, db: &B) -> DbResult<Option<CarId>> {
+        let id = db.delete(CarId::class_hash(), &self.id).await?;
+        Ok(id.map(|id| CarId { id }))
+    }
+}
+impl ClassHash for CarId {
+    fn class_hash() -> String {
+        "a5cdf07dbbc15892dcb64f1553ebb474330393705ce470b22f0194f582234371".to_string()
+    }
+}
+impl From<Car> for ValueCar {
+    fn from(value: Car) -> Self {
+        ValueCar {
+            r#use: value.r#use,
+            embedding: value.embedding,
+            owner: DbLink::Existing(value.owner),
+        }
+    }
+}
+impl From<Car> for CarId {
+    fn from(value: Car) -> Self {
+        CarId { id: value.id }
+    }
+}
+impl From<CarId> for Thing {
+    fn from(value: CarId) -> Self {
+        Thing::from((CarId::class_hash(), value.id))
+    }
+}
+impl Entity for Car {
+    type Id = CarId;
+    type Value = ValueCar;
+    type Serializer = CarSerializer;
+    type OrderBy = CarOrderBy;
+    fn id(&self) -> CarId {
+        CarId {
+            id: self.id.clone(),
+        }
+    }
+}
+#[async_trait]
+impl Repository for Car {
+    async fn create<B: DbBackend>(db: &B, value: ValueCar) -> DbResult<CarId> {
+        value.db_create(db).await
+    }
+    async fn create_get<B: DbBackend>(db: &B, value: ValueCar) -> DbResult<Car> {
+        value.db_create_get(db).await
+    }
+    async fn get<B: DbBackend>(db: &B, id: &CarId) -> DbResult<Option<Car>> {
+        id.db_get(db).await
+    }
+    async fn update<B: DbBackend>(&mut self, db: &B) -> DbResult<Option<CarId>> {
+        self.db_update(db).await
+    }
+    async fn delete<B: DbBackend>(db: &B, id: &CarId) -> DbResult<Option<CarId>> {
+        id.db_delete(db).await
+    }
+    async fn list(db: &Surreal<Any>, query: DbListQuery<CarOrderBy>) -> DbResult<Page<Car>> {
+        Car::db_list(db, query).await
+    }
+    async fn resolve<B: DbBackend>(
+        serializer: CarSerializer,
+        id: String,
+        db: &B,
+    ) -> DbResult<Option<Car>> {
+        serializer.db_resolve(id, db).await
+    }
+}
@@ -0,0 +1,460 @@
+// @generated by db-engine. Do not edit.
+use super::*;
+#[allow(unused_imports)]
+use super::{futures, serde, serde_json, surrealdb};
+pub(super) const DB_DEFINITIONS: &[&str] = &[
+    "DEFINE TABLE `b15f6253519634d53233fa8fe692697f192a15708d5ab1fe8013276a1111592d` SCHEMALESS;",
+    "DEFINE FIELD car_count ON TABLE `b15f6253519634d53233fa8fe692697f192a15708d5ab1fe8013276a1111592d` TYPE option<int> VALUE array::len(cars);",
+    "DEFINE FIELD created_at ON TABLE `b15f6253519634d53233fa8fe692697f192a15708d5ab1fe8013276a1111592d` TYPE option<datetime> VALUE $before OR time::now();",
+    "DEFINE FIELD updated_at ON TABLE `b15f6253519634d53233fa8fe692697f192a15708d5ab1fe8013276a1111592d` TYPE option<datetime> VALUE time::now();",
+    "DEFINE FIELD version ON TABLE `b15f6253519634d53233fa8fe692697f192a15708d5ab1fe8013276a1111592d` TYPE option<int> VALUE ($before OR 0) + 1;",
+    "DEFINE FIELD cars ON TABLE `b15f6253519634d53233fa8fe692697f192a15708d5ab1fe8013276a1111592d` TYPE array<record<`a5cdf07dbbc15892dcb64f1553ebb474330393705ce470b22f0194f582234371`>>;",
+];
+/// Id of a [`Garage`] record.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "b15f6253519634d53233fa8fe692697f192a15708d5ab1fe8013276a1111592d")]
+pub struct GarageId {
+    #[serde(deserialize_with = "thing_to_string")]
+    pub id: String,
+}
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "b15f6253519634d53233fa8fe692697f192a15708d5ab1fe8013276a1111592d")]
+pub struct Garage {
+    #[serde(deserialize_with = "thing_to_string")]
+    pub id: String,
+    pub car_count: usize,
+    pub created_at: surrealdb::sql::Datetime,
+    pub updated_at: surrealdb::sql::Datetime,
+    pub version: u64,
+    pub cars: Vec<Car>,
+}
+/// Values used to create a [`Garage`] record.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "b15f6253519634d53233fa8fe692697f192a15708d5ab1fe8013276a1111592d")]
+pub struct ValueGarage {
+    #[serde(serialize_with = "db_link_to_vec_thing")]
+    pub cars: DbLink<Vec<CarId>, Vec<ValueCar>>,
+}
+/// Stored form of a [`Garage`] record.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "b15f6253519634d53233fa8fe692697f192a15708d5ab1fe8013276a1111592d")]
+pub struct GarageSerializer {
+    pub car_count: usize,
+    pub created_at: surrealdb::sql::Datetime,
+    pub updated_at: surrealdb::sql::Datetime,
+    pub version: u64,
+    pub cars: Vec<Thing>,
+}
+impl ValueGarage {
+    /// Validates the values and creates a new record, creating linked records first.
+    pub async fn db_create<B: DbBackend>(mut self, db: &B) -> DbResult<GarageId> {
+        self.validate()?;
+        if let DbLink::New(v) = self.cars {
+            let futures = v
+                .into_iter()
+                .map(|n| Box::pin(n.db_create(db)))
+                .collect::<Vec<_>>();
+            let result = join_all(futures)
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            self.cars = DbLink::Existing(result);
+        }
+        let id = db
+            .create(GarageId::class_hash(), GarageId::computed_fields(), self)
+            .await?;
+        Ok(GarageId { id })
+    }
+    /// Creates the record and fetches it back.
+    pub async fn db_create_get<B: DbBackend>(self, db: &B) -> DbResult<Garage> {
+        Ok(self.db_create(db).await?.db_get(db).await?.unwrap())
+    }
+    /// Checks the field rules.
+    pub fn validate(&self) -> Result<(), Vec<DbValidationError>> {
+        Ok(())
+    }
+}
+impl Garage {
+    /// Validates the record and stores its current values if its version is unchanged,
+    /// then takes over the new version.
+    pub async fn db_update<B: DbBackend>(&mut self, db: &B) -> DbResult<Option<GarageId>> {
+        self.validate()?;
+        let updated = db
+            .update(
+                GarageId::class_hash(),
+                &self.id,
+                GarageId::computed_fields(),
+                ValueGarage::from(self.clone()),
+                DbGuard {
+                    active: false,
+                    version: Some(self.version),
+                },
+            )
+            .await?;
+        if let Some(updated) = updated {
+            self.version = updated.version.unwrap_or(self.version);
+            return Ok(Some(GarageId { id: updated.id }));
+        }
+        let existing: Option<GarageSerializer> =
+            db.select(GarageId::class_hash(), &self.id).await?;
+        match existing {
+            Some(_) => Err(DbError::Conflict(self.id.clone())),
+            _ => Ok(None),
+        }
+    }
+    /// Stores the fields present in the patch, keeping the others, if the version of
+    /// the record is unchanged, and fetches the record back. Field rules are not
+    /// checked for the patch.
+    pub async fn db_merge<B: DbBackend, P: Serialize + Send + 'static>(
+        &self,
+        db: &B,
+        patch: P,
+    ) -> DbResult<Option<Garage>> {
+        if let Some(updated) = db
+            .merge(
+                GarageId::class_hash(),
+                &self.id,
+                GarageId::computed_fields(),
+                patch,
+                DbGuard {
+                    active: false,
+                    version: Some(self.version),
+                },
+            )
+            .await?
+        {
+            return GarageId { id: updated.id }.db_get(db).await;
+        }
+        let existing: Option<GarageSerializer> =
+            db.select(GarageId::class_hash(), &self.id).await?;
+        match existing {
+            Some(_) => Err(DbError::Conflict(self.id.clone())),
+            _ => Ok(None),
+        }
+    }
+    /// Checks the field rules.
+    pub fn validate(&self) -> Result<(), Vec<DbValidationError>> {
+        Ok(())
+    }
+}
+impl GarageSerializer {
+    /// Resolves the links of a stored record.
+    ///
+    /// Prefetched links include soft-deleted records. A prefetched link to a
+    /// record that no longer exists makes the record unresolvable.
+    pub async fn db_resolve<B: DbBackend>(self, id: String, db: &B) -> DbResult<Option<Garage>> {
+        let Some(cars) = join_all(
+            self.cars
+                .iter()
+                .map(|i| async move {
+                    CarId {
+                        id: i.id.to_string(),
+                    }
+                    .with_deleted()
+                    .db_get(db)
+                    .await
+                })
+                .collect::<Vec<_>>(),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .collect::<Option<Vec<_>>>() else {
+            return Ok(None);
+        };
+        Ok(Some(Garage {
+            id,
+            cars,
+            car_count: self.car_count,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            version: self.version,
+        }))
+    }
+}
+/// Fields a list can be ordered by.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum GarageOrderBy {
+    #[default]
+    Id,
+    CarCount,
+    CreatedAt,
+    UpdatedAt,
+    Version,
+}
+impl DbOrderBy for GarageOrderBy {
+    fn field(&self) -> &'static str {
+        match self {
+            GarageOrderBy::Id => "id",
+            GarageOrderBy::CarCount => "car_count",
+            GarageOrderBy::CreatedAt => "created_at",
+            GarageOrderBy::UpdatedAt => "updated_at",
+            GarageOrderBy::Version => "version",
+        }
+    }
+    fn surql_type(&self) -> &'static str {
+        match self {
+            GarageOrderBy::Id => "record",
+            GarageOrderBy::CarCount => "int",
+            GarageOrderBy::CreatedAt => "datetime",
+            GarageOrderBy::UpdatedAt => "datetime",
+            GarageOrderBy::Version => "int",
+        }
+    }
+}
+impl Garage {
+    /// Fetches one page of records.
+    pub async fn db_list(
+        db: &Surreal<Any>,
+        query: DbListQuery<GarageOrderBy>,
+    ) -> DbResult<Page<Garage>> {
+        let conditions = vec![];
+        let (records, has_more, total) = db_list_records::<_, DbRecord<GarageSerializer>>(
+            db,
+            vec![GarageId::class_hash()],
+            &query,
+            conditions,
+        )
+        .await?;
+        let next_cursor = db_next_cursor(&query, has_more, GarageId::class_hash(), records.last());
+        let items = join_all(
+            records
+                .into_iter()
+                .map(|record| record.value.db_resolve(record.id, db)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        Ok(Page {
+            items,
+            next_cursor,
+            total,
+            has_more,
+        })
+    }
+    /// Streams all records, fetching them in batches. A `batch_size` of 0 is treated as 1.
+    pub fn db_stream(db: &Surreal<Any>, batch_size: u64) -> BoxStream<'_, DbResult<Garage>> {
+        let batch_size = batch_size.max(1);
+        futures::stream::try_unfold(Some(None), move |cursor| async move {
+            let Some(cursor) = cursor else {
+                return DbResult::Ok(None);
+            };
+            let page = Garage::db_list(db, DbListQuery::cursor(cursor, batch_size)).await?;
+            let items = futures::stream::iter(page.items.into_iter().map(Ok));
+            Ok(Some((items, page.next_cursor.map(Some))))
+        })
+        .try_flatten()
+        .boxed()
+    }
+}
+/// Fields an aggregation can be grouped by.
+#[derive(Debug, Clone, Copy)]
+pub enum GarageGroupBy {
+    CarCount,
+    Version,
+}
+impl GarageGroupBy {
+    fn field(&self) -> &'static str {
+        match *self {
+            GarageGroupBy::CarCount => "car_count",
+            GarageGroupBy::Version => "version",
+        }
+    }
+}
+/// Value of the group an aggregated row belongs to.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+pub enum GarageGroupKey {
+    CarCount(usize),
+    Version(u64),
+}
+/// Numeric fields an aggregation can summarize.
+#[derive(Debug, Clone, Copy)]
+pub enum GarageMetric {
+    CarCount,
+    Version,
+}
+impl GarageMetric {
+    fn field(&self) -> &'static str {
+        match *self {
+            GarageMetric::CarCount => "car_count",
+            GarageMetric::Version => "version",
+        }
+    }
+}
+/// Builder for aggregation queries.
+#[derive(Debug, Clone, Default)]
+pub struct GarageAggregate {
+    group_by: Option<GarageGroupBy>,
+    metric: Option<GarageMetric>,
+}
+impl GarageAggregate {
+    /// Groups the rows by the given field.
+    pub fn group_by(mut self, group_by: GarageGroupBy) -> Self {
+        self.group_by = Some(group_by);
+        self
+    }
+    /// Computes sum, average, minimum and maximum of the given field.
+    pub fn metric(mut self, metric: GarageMetric) -> Self {
+        self.metric = Some(metric);
+        self
+    }
+    /// Runs the aggregation.
+    pub async fn fetch(self, db: &Surreal<Any>) -> DbResult<Vec<DbAggregate<GarageGroupKey>>> {
+        let mut fields = vec!["count() AS count".to_string()];
+        if let Some(metric) = self.metric {
+            let f = metric.field();
+            fields
+                .push(
+                    format!(
+                        "math::sum({f}) AS sum, math::mean({f}) AS avg, math::min({f}) AS min, math::max({f}) AS max"
+                    ),
+                );
+        }
+        let group = match self.group_by {
+            Some(group_by) => {
+                fields.insert(0, format!("{} AS key", group_by.field()));
+                "GROUP BY key"
+            }
+            None => "GROUP ALL",
+        };
+        let sql = format!(
+            "SELECT {} FROM type::table($table){} {}",
+            fields.join(", "),
+            "",
+            group
+        );
+        let mut response = db
+            .query(sql)
+            .bind(("table", GarageId::class_hash()))
+            .await?;
+        Ok(match self.group_by {
+            None => response.take(0)?,
+            Some(GarageGroupBy::CarCount) => response
+                .take::<Vec<DbAggregate<usize>>>(0)?
+                .into_iter()
+                .map(|row| row.map_key(GarageGroupKey::CarCount))
+                .collect(),
+            Some(GarageGroupBy::Version) => response
+                .take::<Vec<DbAggregate<u64>>>(0)?
+                .into_iter()
+                .map(|row| row.map_key(GarageGroupKey::Version))
+                .collect(),
+        })
+    }
+}
+impl Garage {
+    /// Starts an aggregation query.
+    pub fn db_aggregate() -> GarageAggregate {
+        GarageAggregate::default()
+    }
+}
+impl GarageId {
+    /// Includes soft-deleted records in the following lookup.
+    pub fn with_deleted(&self) -> WithDeleted<GarageId> {
+        WithDeleted(self.clone())
+    }
+    /// Fetches the record unless it was soft-deleted.
+    pub async fn db_get<B: DbBackend>(&self, db: &B) -> DbResult<Option<Garage>> {
+        self.with_deleted().db_get(db).await
+    }
+}
+impl WithDeleted<GarageId> {
+    /// Fetches the record, including a soft-deleted one.
+    pub async fn db_get<B: DbBackend>(&self, db: &B) -> DbResult<Option<Garage>> {
+        let Some(deserialized): Option<GarageSerializer> =
+            db.select(GarageId::class_hash(), &self.0.id).await?
+        else {
+            return Ok(None);
+        };
+        deserialized.db_resolve(self.0.id.clone(), db).await
+    }
+}
+impl GarageId {
+    /// Removes the record.
+    pub async fn db_delete<B: DbBackend>(&self, db: &B) -> DbResult<Option<GarageId>> {
+        let id = db.delete(GarageId::class_hash(), &self.id).await?;
+        Ok(id.map(|id| GarageId { id }))
+    }
+}
+impl ClassHash for GarageId {
+    fn class_hash() -> String {
+        "b15f6253519634d53233fa8fe692697f192a15708d5ab1fe8013276a1111592d".to_string()
+    }
+    fn computed_fields() -> &'static [DbComputed] {
+        &[
+            DbComputed::Surql("car_count"),
+            DbComputed::CreatedAt("created_at"),
+            DbComputed::Now("updated_at"),
+            DbComputed::Version("version"),
+        ]
+    }
+}
+impl From<Garage> for ValueGarage {
+    fn from(value: Garage) -> Self {
+        ValueGarage {
+            cars: DbLink::Existing(value.cars.into_iter().map(|i| CarId { id: i.id }).collect()),
+        }
+    }
+}
+impl From<Garage> for GarageId {
+    fn from(value: Garage) -> Self {
+        GarageId { id: value.id }
+    }
+}
+impl From<GarageId> for Thing {
+    fn from(value: GarageId) -> Self {
+        Thing::from((GarageId::class_hash(), value.id))
+    }
+}
+impl Entity for Garage {
+    type Id = GarageId;
+    type Value = ValueGarage;
+    type Serializer = GarageSerializer;
+    type OrderBy = GarageOrderBy;
+    fn id(&self) -> GarageId {
+        GarageId {
+            id: self.id.clone(),
+        }
+    }
+}
+#[async_trait]
+impl Repository for Garage {
+    async fn create<B: DbBackend>(db: &B, value: ValueGarage) -> DbResult<GarageId> {
+        value.db_create(db).await
+    }
+    async fn create_get<B: DbBackend>(db: &B, value: ValueGarage) -> DbResult<Garage> {
+        value.db_create_get(db).await
+    }
+    async fn get<B: DbBackend>(db: &B, id: &GarageId) -> DbResult<Option<Garage>> {
+        id.db_get(db).await
+    }
+    async fn update<B: DbBackend>(&mut self, db: &B) -> DbResult<Option<GarageId>> {
+        self.db_update(db).await
+    }
+    async fn delete<B: DbBackend>(db: &B, id: &GarageId) -> DbResult<Option<GarageId>> {
+        id.db_delete(db).await
+    }
+    async fn list(db: &Surreal<Any>, query: DbListQuery<GarageOrderBy>) -> DbResult<Page<Garage>> {
+        Garage::db_list(db, query).await
+    }
+    async fn resolve<B: DbBackend>(
+        serializer: GarageSerializer,
+        id: String,
+        db: &B,
+    ) -> DbResult<Option<Garage>> {
+        serializer.db_resolve(id, db).await
+    }
+}
@@ -0,0 +1,390 @@
+// @generated by db-engine. Do not edit.
+use super::*;
+#[allow(unused_imports)]
+use super::{futures, serde, serde_json, surrealdb};
+pub(super) const DB_DEFINITIONS: &[&str] = &[
+    "DEFINE TABLE `5ed8944a85a9763fd315852f448cb7de36c5e928e13b3be427f98f7dc455f141` SCHEMALESS;",
+    "DEFINE FIELD nick ON TABLE `5ed8944a85a9763fd315852f448cb7de36c5e928e13b3be427f98f7dc455f141` TYPE string;",
+    "DEFINE FIELD PersonEnumBase ON TABLE `5ed8944a85a9763fd315852f448cb7de36c5e928e13b3be427f98f7dc455f141` TYPE record<`6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d`>;",
+];
+/// Id of a [`Guest`] record.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "5ed8944a85a9763fd315852f448cb7de36c5e928e13b3be427f98f7dc455f141")]
+pub struct GuestId {
+    #[serde(deserialize_with = "thing_to_string")]
+    pub id: String,
+}
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "5ed8944a85a9763fd315852f448cb7de36c5e928e13b3be427f98f7dc455f141")]
+pub struct Guest {
+    #[serde(deserialize_with = "thing_to_string")]
+    pub id: String,
+    pub nick: String,
+    #[serde(rename = "PersonEnumBase")]
+    pub person_enum_base: PersonId,
+}
+/// Values used to create a [`Guest`] record.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "5ed8944a85a9763fd315852f448cb7de36c5e928e13b3be427f98f7dc455f141")]
+pub struct ValueGuest {
+    pub nick: String,
+    #[serde(rename = "PersonEnumBase")]
+    #[serde(serialize_with = "db_link_to_thing")]
+    pub person_enum_base: DbLink<PersonId, ValuePerson>,
+}
+/// Stored form of a [`Guest`] record.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "5ed8944a85a9763fd315852f448cb7de36c5e928e13b3be427f98f7dc455f141")]
+pub struct GuestSerializer {
+    pub nick: String,
+    #[serde(rename = "PersonEnumBase")]
+    pub person_enum_base: Thing,
+}
+impl ValueGuest {
+    /// Validates the values and creates a new record, creating linked records first.
+    pub async fn db_create<B: DbBackend>(mut self, db: &B) -> DbResult<GuestId> {
+        self.validate()?;
+        if let DbLink::New(n) = self.person_enum_base {
+            let result = Box::pin(n.db_create(db)).await?;
+            self.person_enum_base = DbLink::Existing(result);
+        }
+        let id = db
+            .create(GuestId::class_hash(), GuestId::computed_fields(), self)
+            .await?;
+        Ok(GuestId { id })
+    }
+    /// Creates the record and fetches it back.
+    pub async fn db_create_get<B: DbBackend>(self, db: &B) -> DbResult<Guest> {
+        Ok(self.db_create(db).await?.db_get(db).await?.unwrap())
+    }
+    /// Checks the field rules.
+    pub fn validate(&self) -> Result<(), Vec<DbValidationError>> {
+        Ok(())
+    }
+}
+impl Guest {
+    /// Validates the record and stores its current values.
+    pub async fn db_update<B: DbBackend>(&self, db: &B) -> DbResult<Option<GuestId>> {
+        self.validate()?;
+        let updated = db
+            .update(
+                GuestId::class_hash(),
+                &self.id,
+                GuestId::computed_fields(),
+                ValueGuest::from(self.clone()),
+                DbGuard {
+                    active: false,
+                    version: None,
+                },
+            )
+            .await?;
+        Ok(updated.map(|u| GuestId { id: u.id }))
+    }
+    /// Stores the fields present in the patch, keeping the others, and fetches the
+    /// record back. Field rules are not checked for the patch.
+    pub async fn db_merge<B: DbBackend, P: Serialize + Send + 'static>(
+        &self,
+        db: &B,
+        patch: P,
+    ) -> DbResult<Option<Guest>> {
+        let Some(updated) = db
+            .merge(
+                GuestId::class_hash(),
+                &self.id,
+                GuestId::computed_fields(),
+                patch,
+                DbGuard {
+                    active: false,
+                    version: None,
+                },
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        GuestId { id: updated.id }.db_get(db).await
+    }
+    /// Checks the field rules.
+    pub fn validate(&self) -> Result<(), Vec<DbValidationError>> {
+        Ok(())
+    }
+}
+impl GuestSerializer {
+    /// Resolves the links of a stored record.
+    ///
+    /// Prefetched links include soft-deleted records. A prefetched link to a
+    /// record that no longer exists makes the record unresolvable.
+    pub async fn db_resolve<B: DbBackend>(self, id: String, _db: &B) -> DbResult<Option<Guest>> {
+        let person_enum_base = PersonId {
+            id: self.person_enum_base.id.to_string(),
+        };
+        Ok(Some(Guest {
+            id,
+            person_enum_base,
+            nick: self.nick,
+        }))
+    }
+}
+/// Fields a list can be ordered by.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum GuestOrderBy {
+    #[default]
+    Id,
+    Nick,
+}
+impl DbOrderBy for GuestOrderBy {
+    fn field(&self) -> &'static str {
+        match self {
+            GuestOrderBy::Id => "id",
+            GuestOrderBy::Nick => "nick",
+        }
+    }
+    fn surql_type(&self) -> &'static str {
+        match self {
+            GuestOrderBy::Id => "record",
+            GuestOrderBy::Nick => "string",
+        }
+    }
+}
+impl Guest {
+    /// Fetches one page of records.
+    pub async fn db_list(
+        db: &Surreal<Any>,
+        query: DbListQuery<GuestOrderBy>,
+    ) -> DbResult<Page<Guest>> {
+        let conditions = vec![];
+        let (records, has_more, total) = db_list_records::<_, DbRecord<GuestSerializer>>(
+            db,
+            vec![GuestId::class_hash()],
+            &query,
+            conditions,
+        )
+        .await?;
+        let next_cursor = db_next_cursor(&query, has_more, GuestId::class_hash(), records.last());
+        let items = join_all(
+            records
+                .into_iter()
+                .map(|record| record.value.db_resolve(record.id, db)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        Ok(Page {
+            items,
+            next_cursor,
+            total,
+            has_more,
+        })
+    }
+    /// Streams all records, fetching them in batches. A `batch_size` of 0 is treated as 1.
+    pub fn db_stream(db: &Surreal<Any>, batch_size: u64) -> BoxStream<'_, DbResult<Guest>> {
+        let batch_size = batch_size.max(1);
+        futures::stream::try_unfold(Some(None), move |cursor| async move {
+            let Some(cursor) = cursor else {
+                return DbResult::Ok(None);
+            };
+            let page = Guest::db_list(db, DbListQuery::cursor(cursor, batch_size)).await?;
+            let items = futures::stream::iter(page.items.into_iter().map(Ok));
+            Ok(Some((items, page.next_cursor.map(Some))))
+        })
+        .try_flatten()
+        .boxed()
+    }
+}
+/// Fields an aggregation can be grouped by.
+#[derive(Debug, Clone, Copy)]
+pub enum GuestGroupBy {
+    Nick,
+}
+impl GuestGroupBy {
+    fn field(&self) -> &'static str {
+        match *self {
+            GuestGroupBy::Nick => "nick",
+        }
+    }
+}
+/// Value of the group an aggregated row belongs to.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+pub enum GuestGroupKey {
+    Nick(String),
+}
+/// Numeric fields an aggregation can summarize.
+#[derive(Debug, Clone, Copy)]
+pub enum GuestMetric {}
+impl GuestMetric {
+    fn field(&self) -> &'static str {
+        match *self {}
+    }
+}
+/// Builder for aggregation queries.
+#[derive(Debug, Clone, Default)]
+pub struct GuestAggregate {
+    group_by: Option<GuestGroupBy>,
+    metric: Option<GuestMetric>,
+}
+impl GuestAggregate {
+    /// Groups the rows by the given field.
+    pub fn group_by(mut self, group_by: GuestGroupBy) -> Self {
+        self.group_by = Some(group_by);
+        self
+    }
+    /// Computes sum, average, minimum and maximum of the given field.
+    pub fn metric(mut self, metric: GuestMetric) -> Self {
+        self.metric = Some(metric);
+        self
+    }
+    /// Runs the aggregation.
+    pub async fn fetch(self, db: &Surreal<Any>) -> DbResult<Vec<DbAggregate<GuestGroupKey>>> {
+        let mut fields = vec!["count() AS count".to_string()];
+        if let Some(metric) = self.metric {
+            let f = metric.field();
+            fields
+                .push(
+                    format!(
+                        "math::sum({f}) AS sum, math::mean({f}) AS avg, math::min({f}) AS min, math::max({f}) AS max"
+                    ),
+                );
+        }
+        let group = match self.group_by {
+            Some(group_by) => {
+                fields.insert(0, format!("{} AS key", group_by.field()));
+                "GROUP BY key"
+            }
+            None => "GROUP ALL",
+        };
+        let sql = format!(
+            "SELECT {} FROM type::table($table){} {}",
+            fields.join(", "),
+            "",
+            group
+        );
+        let mut response = db.query(sql).bind(("table", GuestId::class_hash())).await?;
+        Ok(match self.group_by {
+            None => response.take(0)?,
+            Some(GuestGroupBy::Nick) => response
+                .take::<Vec<DbAggregate<String>>>(0)?
+                .into_iter()
+                .map(|row| row.map_key(GuestGroupKey::Nick))
+                .collect(),
+        })
+    }
+}
+impl Guest {
+    /// Starts an aggregation query.
+    pub fn db_aggregate() -> GuestAggregate {
+        GuestAggregate::default()
+    }
+}
+impl GuestId {
+    /// Includes soft-deleted records in the following lookup.
+    pub fn with_deleted(&self) -> WithDeleted<GuestId> {
+        WithDeleted(self.clone())
+    }
+    /// Fetches the record unless it was soft-deleted.
+    pub async fn db_get<B: DbBackend>(&self, db: &B) -> DbResult<Option<Guest>> {
+        self.with_deleted().db_get(db).await
+    }
+}
+impl WithDeleted<GuestId> {
+    /// Fetches the record, including a soft-deleted one.
+    pub async fn db_get<B: DbBackend>(&self, db: &B) -> DbResult<Option<Guest>> {
+        let Some(deserialized): Option<GuestSerializer> =
+            db.select(GuestId::class_hash(), &self.0.id).await?
+        else {
+            return Ok(None);
+        };
+        deserialized.db_resolve(self.0.id.clone(), db).await
+    }
+}
+impl GuestId {
+    /// Removes the record.
+    pub async fn db_delete<B: DbBackend>(&self, db: &B) -> DbResult<Option<GuestId>> {
+        let id = db.delete(GuestId::class_hash(), &self.id).await?;
+        Ok(id.map(|id| GuestId { id }))
+    }
+}
+impl ClassHash for GuestId {
+    fn class_hash() -> String {
+        "5ed8944a85a9763fd315852f448cb7de36c5e928e13b3be427f98f7dc455f141".to_string()
+    }
+}
+impl From<Guest> for ValueGuest {
+    fn from(value: Guest) -> Self {
+        ValueGuest {
+            nick: value.nick,
+            person_enum_base: DbLink::Existing(value.person_enum_base),
+        }
+    }
+}
+impl From<Guest> for GuestId {
+    fn from(value: Guest) -> Self {
+        GuestId { id: value.id }
+    }
+}
+impl From<GuestId> for Thing {
+    fn from(value: GuestId) -> Self {
+        Thing::from((GuestId::class_hash(), value.id))
+    }
+}
+#[async_trait]
+impl DbExtend<PersonEnumBase> for Guest {
+    async fn db_extend<B: DbBackend>(self, db: &B) -> DbResult<PersonEnumBase> {
+        let base = self.person_enum_base.db_get(db).await?.unwrap();
+        Ok(PersonEnumBase {
+            name: base.name,
+            age: base.age,
+            active: base.active,
+        })
+    }
+}
+impl Entity for Guest {
+    type Id = GuestId;
+    type Value = ValueGuest;
+    type Serializer = GuestSerializer;
+    type OrderBy = GuestOrderBy;
+    fn id(&self) -> GuestId {
+        GuestId {
+            id: self.id.clone(),
+        }
+    }
+}
+#[async_trait]
+impl Repository for Guest {
+    async fn create<B: DbBackend>(db: &B, value: ValueGuest) -> DbResult<GuestId> {
+        value.db_create(db).await
+    }
+    async fn create_get<B: DbBackend>(db: &B, value: ValueGuest) -> DbResult<Guest> {
+        value.db_create_get(db).await
+    }
+    async fn get<B: DbBackend>(db: &B, id: &GuestId) -> DbResult<Option<Guest>> {
+        id.db_get(db).await
+    }
+    async fn update<B: DbBackend>(&mut self, db: &B) -> DbResult<Option<GuestId>> {
+        self.db_update(db).await
+    }
+    async fn delete<B: DbBackend>(db: &B, id: &GuestId) -> DbResult<Option<GuestId>> {
+        id.db_delete(db).await
+    }
+    async fn list(db: &Surreal<Any>, query: DbListQuery<GuestOrderBy>) -> DbResult<Page<Guest>> {
+        Guest::db_list(db, query).await
+    }
+    async fn resolve<B: DbBackend>(
+        serializer: GuestSerializer,
+        id: String,
+        db: &B,
+    ) -> DbResult<Option<Guest>> {
+        serializer.db_resolve(id, db).await
+    }
+}
@@ -0,0 +1,54 @@
+// @generated by db-engine. Do not edit.
+use db_engine::runtime::v1::async_trait::async_trait;
+use db_engine::runtime::v1::futures::future::join_all;
+use db_engine::runtime::v1::futures::stream::{BoxStream, StreamExt, TryStreamExt};
+use db_engine::runtime::v1::serde::{Deserialize, Serialize};
+use db_engine::runtime::v1::surrealdb::sql::Thing;
+use db_engine::runtime::v1::surrealdb::{engine::any::Any, Surreal};
+use db_engine::runtime::v1::*;
+#[allow(unused_imports)]
+use db_engine::runtime::v1::{futures, serde, serde_json, surrealdb};
+#[derive(Debug, Clone)]
+pub struct WithDeleted<T>(pub T);
+/// SurrealQL statements defining the schema, grouped by class.
+pub const DB_DEFINITIONS: &[&[&str]] = &[
+    person::DB_DEFINITIONS,
+    user::DB_DEFINITIONS,
+    guest::DB_DEFINITIONS,
+    car::DB_DEFINITIONS,
+    garage::DB_DEFINITIONS,
+];
+pub async fn db_define(db: &Surreal<Any>) -> DbResult<()> {
+    db.query(DB_DEFINITIONS.concat().join("\n"))
+        .await?
+        .check()?;
+    Ok(())
+}
+/// Account state of a user.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+pub enum Status {
+    #[serde(rename = "active")]
+    Active,
+    #[serde(rename = "suspended")]
+    Suspended,
+}
+/// Postal address.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+pub struct Address {
+    pub street: String,
+    pub city: String,
+}
+mod car;
+pub use car::*;
+mod garage;
+pub use garage::*;
+mod guest;
+pub use guest::*;
+mod person;
+pub use person::*;
+mod person_enum;
+pub use person_enum::*;
+mod user;
+pub use user::*;
@@ -0,0 +1,550 @@
+// @generated by db-engine. Do not edit.
+use super::*;
+#[allow(unused_imports)]
+use super::{futures, serde, serde_json, surrealdb};
+pub(super) const DB_DEFINITIONS: &[&str] = &[
+    "DEFINE TABLE `6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d` SCHEMALESS COMMENT \"A person known to the system.\";",
+    "DEFINE FIELD name ON TABLE `6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d` TYPE string ASSERT string::len($value) >= 1 AND string::len($value) <= 100;",
+    "DEFINE FIELD age ON TABLE `6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d` TYPE int ASSERT $value <= 150 COMMENT \"Age in years.\";",
+    "DEFINE FIELD active ON TABLE `6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d` TYPE bool DEFAULT true;",
+    "DEFINE TABLE `6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d_history` SCHEMALESS;",
+    "DEFINE EVENT history ON TABLE `6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d` THEN (CREATE `6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d_history` CONTENT { record: $before.id OR $after.id, event: $event, at: time::now(), data: $after });",
+];
+/// Id of a [`Person`] record.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d")]
+pub struct PersonId {
+    #[serde(deserialize_with = "thing_to_string")]
+    pub id: String,
+}
+/// A person known to the system.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d")]
+pub struct Person {
+    #[serde(deserialize_with = "thing_to_string")]
+    pub id: String,
+    pub name: String,
+    /// Age in years.
+    pub age: u16,
+    pub active: bool,
+}
+/// Values used to create a [`Person`] record.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d")]
+pub struct ValuePerson {
+    pub name: String,
+    /// Age in years.
+    pub age: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+}
+/// Stored form of a [`Person`] record.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d")]
+pub struct PersonSerializer {
+    pub name: String,
+    /// Age in years.
+    pub age: u16,
+    pub active: bool,
+}
+impl ValuePerson {
+    /// Validates the values and creates a new record, creating linked records first.
+    pub async fn db_create<B: DbBackend>(mut self, db: &B) -> DbResult<PersonId> {
+        if self.active.is_none() {
+            self.active = Some(true);
+        }
+        self.validate()?;
+        let id = db
+            .create(PersonId::class_hash(), PersonId::computed_fields(), self)
+            .await?;
+        Ok(PersonId { id })
+    }
+    /// Creates the record and fetches it back.
+    pub async fn db_create_get<B: DbBackend>(self, db: &B) -> DbResult<Person> {
+        Ok(self.db_create(db).await?.db_get(db).await?.unwrap())
+    }
+    /// Checks the field rules.
+    pub fn validate(&self) -> Result<(), Vec<DbValidationError>> {
+        let mut errors = vec![];
+        {
+            let value = &self.name;
+            if value.chars().count() < 1usize {
+                errors.push(DbValidationError {
+                    field: "name".to_string(),
+                    message: "must be at least 1 long".to_string(),
+                });
+            }
+            if value.chars().count() > 100usize {
+                errors.push(DbValidationError {
+                    field: "name".to_string(),
+                    message: "must be at most 100 long".to_string(),
+                });
+            }
+        }
+        {
+            let value = &self.age;
+            if *value > 150 {
+                errors.push(DbValidationError {
+                    field: "age".to_string(),
+                    message: "must be at most 150".to_string(),
+                });
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+impl Person {
+    /// Validates the record and stores its current values.
+    pub async fn db_update<B: DbBackend>(&self, db: &B) -> DbResult<Option<PersonId>> {
+        self.validate()?;
+        let updated = db
+            .update(
+                PersonId::class_hash(),
+                &self.id,
+                PersonId::computed_fields(),
+                ValuePerson::from(self.clone()),
+                DbGuard {
+                    active: false,
+                    version: None,
+                },
+            )
+            .await?;
+        Ok(updated.map(|u| PersonId { id: u.id }))
+    }
+    /// Stores the fields present in the patch, keeping the others, and fetches the
+    /// record back. Field rules are not checked for the patch.
+    pub async fn db_merge<B: DbBackend, P: Serialize + Send + 'static>(
+        &self,
+        db: &B,
+        patch: P,
+    ) -> DbResult<Option<Person>> {
+        let Some(updated) = db
+            .merge(
+                PersonId::class_hash(),
+                &self.id,
+                PersonId::computed_fields(),
+                patch,
+                DbGuard {
+                    active: false,
+                    version: None,
+                },
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        PersonId { id: updated.id }.db_get(db).await
+    }
+    /// Checks the field rules.
+    pub fn validate(&self) -> Result<(), Vec<DbValidationError>> {
+        let mut errors = vec![];
+        {
+            let value = &self.name;
+            if value.chars().count() < 1usize {
+                errors.push(DbValidationError {
+                    field: "name".to_string(),
+                    message: "must be at least 1 long".to_string(),
+                });
+            }
+            if value.chars().count() > 100usize {
+                errors.push(DbValidationError {
+                    field: "name".to_string(),
+                    message: "must be at most 100 long".to_string(),
+                });
+            }
+        }
+        {
+            let value = &self.age;
+            if *value > 150 {
+                errors.push(DbValidationError {
+                    field: "age".to_string(),
+                    message: "must be at most 150".to_string(),
+                });
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+impl PersonSerializer {
+    /// Resolves the links of a stored record.
+    ///
+    /// Prefetched links include soft-deleted records. A prefetched link to a
+    /// record that no longer exists makes the record unresolvable.
+    pub async fn db_resolve<B: DbBackend>(self, id: String, _db: &B) -> DbResult<Option<Person>> {
+        Ok(Some(Person {
+            id,
+            name: self.name,
+            age: self.age,
+            active: self.active,
+        }))
+    }
+}
+/// One recorded change of a record.
+#[derive(Debug, Clone)]
+pub struct PersonHistory {
+    pub event: String,
+    pub at: surrealdb::sql::Datetime,
+    pub value: Option<Person>,
+}
+#[derive(Deserialize)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+struct PersonHistoryRow {
+    event: String,
+    at: surrealdb::sql::Datetime,
+    data: Option<PersonSerializer>,
+}
+impl PersonHistoryRow {
+    async fn db_resolve(self, id: String, db: &Surreal<Any>) -> DbResult<PersonHistory> {
+        let value = match self.data {
+            Some(data) => data.db_resolve(id, db).await?,
+            None => None,
+        };
+        Ok(PersonHistory {
+            event: self.event,
+            at: self.at,
+            value,
+        })
+    }
+}
+impl PersonId {
+    /// Fetches all recorded changes of the record, oldest first.
+    pub async fn db_history(&self, db: &Surreal<Any>) -> DbResult<Vec<PersonHistory>> {
+        let rows: Vec<PersonHistoryRow> = db
+            .query(
+                "SELECT event, at, data FROM type::table($table) WHERE record = $record ORDER BY at",
+            )
+            .bind((
+                "table",
+                "6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d_history",
+            ))
+            .bind(("record", Thing::from(self.clone())))
+            .await?
+            .take(0)?;
+        let mut history = Vec::with_capacity(rows.len());
+        for row in rows {
+            history.push(row.db_resolve(self.id.clone(), db).await?);
+        }
+        Ok(history)
+    }
+    /// Fetches the record as it was at the given time, from its history.
+    pub async fn db_get_as_of(
+        &self,
+        db: &Surreal<Any>,
+        at: surrealdb::sql::Datetime,
+    ) -> DbResult<Option<Person>> {
+        let row: Option<PersonHistoryRow> = db
+            .query(
+                "SELECT event, at, data FROM type::table($table) WHERE record = $record AND at <= $at ORDER BY at DESC LIMIT 1",
+            )
+            .bind((
+                "table",
+                "6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d_history",
+            ))
+            .bind(("record", Thing::from(self.clone())))
+            .bind(("at", at))
+            .await?
+            .take(0)?;
+        let Some(row) = row else { return Ok(None) };
+        Ok(row.db_resolve(self.id.clone(), db).await?.value)
+    }
+}
+/// Fields a list can be ordered by.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum PersonOrderBy {
+    #[default]
+    Id,
+    Name,
+    Age,
+    Active,
+}
+impl DbOrderBy for PersonOrderBy {
+    fn field(&self) -> &'static str {
+        match self {
+            PersonOrderBy::Id => "id",
+            PersonOrderBy::Name => "name",
+            PersonOrderBy::Age => "age",
+            PersonOrderBy::Active => "active",
+        }
+    }
+    fn surql_type(&self) -> &'static str {
+        match self {
+            PersonOrderBy::Id => "record",
+            PersonOrderBy::Name => "string",
+            PersonOrderBy::Age => "int",
+            PersonOrderBy::Active => "bool",
+        }
+    }
+}
+impl Person {
+    /// Fetches one page of records.
+    pub async fn db_list(
+        db: &Surreal<Any>,
+        query: DbListQuery<PersonOrderBy>,
+    ) -> DbResult<Page<Person>> {
+        let conditions = vec![];
+        let (records, has_more, total) = db_list_records::<_, DbRecord<PersonSerializer>>(
+            db,
+            vec![PersonId::class_hash()],
+            &query,
+            conditions,
+        )
+        .await?;
+        let next_cursor = db_next_cursor(&query, has_more, PersonId::class_hash(), records.last());
+        let items = join_all(
+            records
+                .into_iter()
+                .map(|record| record.value.db_resolve(record.id, db)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        Ok(Page {
+            items,
+            next_cursor,
+            total,
+            has_more,
+        })
+    }
+    /// Streams all records, fetching them in batches. A `batch_size` of 0 is treated as 1.
+    pub fn db_stream(db: &Surreal<Any>, batch_size: u64) -> BoxStream<'_, DbResult<Person>> {
+        let batch_size = batch_size.max(1);
+        futures::stream::try_unfold(Some(None), move |cursor| async move {
+            let Some(cursor) = cursor else {
+                return DbResult::Ok(None);
+            };
+            let page = Person::db_list(db, DbListQuery::cursor(cursor, batch_size)).await?;
+            let items = futures::stream::iter(page.items.into_iter().map(Ok));
+            Ok(Some((items, page.next_cursor.map(Some))))
+        })
+        .try_flatten()
+        .boxed()
+    }
+}
+/// Fields an aggregation can be grouped by.
+#[derive(Debug, Clone, Copy)]
+pub enum PersonGroupBy {
+    Name,
+    Age,
+    Active,
+}
+impl PersonGroupBy {
+    fn field(&self) -> &'static str {
+        match *self {
+            PersonGroupBy::Name => "name",
+            PersonGroupBy::Age => "age",
+            PersonGroupBy::Active => "active",
+        }
+    }
+}
+/// Value of the group an aggregated row belongs to.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+pub enum PersonGroupKey {
+    Name(String),
+    Age(u16),
+    Active(bool),
+}
+/// Numeric fields an aggregation can summarize.
+#[derive(Debug, Clone, Copy)]
+pub enum PersonMetric {
+    Age,
+}
+impl PersonMetric {
+    fn field(&self) -> &'static str {
+        match *self {
+            PersonMetric::Age => "age",
+        }
+    }
+}
+/// Builder for aggregation queries.
+#[derive(Debug, Clone, Default)]
+pub struct PersonAggregate {
+    group_by: Option<PersonGroupBy>,
+    metric: Option<PersonMetric>,
+}
+impl PersonAggregate {
+    /// Groups the rows by the given field.
+    pub fn group_by(mut self, group_by: PersonGroupBy) -> Self {
+        self.group_by = Some(group_by);
+        self
+    }
+    /// Computes sum, average, minimum and maximum of the given field.
+    pub fn metric(mut self, metric: PersonMetric) -> Self {
+        self.metric = Some(metric);
+        self
+    }
+    /// Runs the aggregation.
+    pub async fn fetch(self, db: &Surreal<Any>) -> DbResult<Vec<DbAggregate<PersonGroupKey>>> {
+        let mut fields = vec!["count() AS count".to_string()];
+        if let Some(metric) = self.metric {
+            let f = metric.field();
+            fields
+                .push(
+                    format!(
+                        "math::sum({f}) AS sum, math::mean({f}) AS avg, math::min({f}) AS min, math::max({f}) AS max"
+                    ),
+                );
+        }
+        let group = match self.group_by {
+            Some(group_by) => {
+                fields.insert(0, format!("{} AS key", group_by.field()));
+                "GROUP BY key"
+            }
+            None => "GROUP ALL",
+        };
+        let sql = format!(
+            "SELECT {} FROM type::table($table){} {}",
+            fields.join(", "),
+            "",
+            group
+        );
+        let mut response = db
+            .query(sql)
+            .bind(("table", PersonId::class_hash()))
+            .await?;
+        Ok(match self.group_by {
+            None => response.take(0)?,
+            Some(PersonGroupBy::Name) => response
+                .take::<Vec<DbAggregate<String>>>(0)?
+                .into_iter()
+                .map(|row| row.map_key(PersonGroupKey::Name))
+                .collect(),
+            Some(PersonGroupBy::Age) => response
+                .take::<Vec<DbAggregate<u16>>>(0)?
+                .into_iter()
+                .map(|row| row.map_key(PersonGroupKey::Age))
+                .collect(),
+            Some(PersonGroupBy::Active) => response
+                .take::<Vec<DbAggregate<bool>>>(0)?
+                .into_iter()
+                .map(|row| row.map_key(PersonGroupKey::Active))
+                .collect(),
+        })
+    }
+}
+impl Person {
+    /// Starts an aggregation query.
+    pub fn db_aggregate() -> PersonAggregate {
+        PersonAggregate::default()
+    }
+}
+impl PersonId {
+    /// Includes soft-deleted records in the following lookup.
+    pub fn with_deleted(&self) -> WithDeleted<PersonId> {
+        WithDeleted(self.clone())
+    }
+    /// Fetches the record unless it was soft-deleted.
+    pub async fn db_get<B: DbBackend>(&self, db: &B) -> DbResult<Option<Person>> {
+        self.with_deleted().db_get(db).await
+    }
+}
+impl WithDeleted<PersonId> {
+    /// Fetches the record, including a soft-deleted one.
+    pub async fn db_get<B: DbBackend>(&self, db: &B) -> DbResult<Option<Person>> {
+        let Some(deserialized): Option<PersonSerializer> =
+            db.select(PersonId::class_hash(), &self.0.id).await?
+        else {
+            return Ok(None);
+        };
+        deserialized.db_resolve(self.0.id.clone(), db).await
+    }
+}
+impl PersonId {
+    /// Removes the record.
+    pub async fn db_delete<B: DbBackend>(&self, db: &B) -> DbResult<Option<PersonId>> {
+        let id = db.delete(PersonId::class_hash(), &self.id).await?;
+        Ok(id.map(|id| PersonId { id }))
+    }
+}
+impl ClassHash for PersonId {
+    fn class_hash() -> String {
+        "6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d".to_string()
+    }
+}
+impl From<Person> for ValuePerson {
+    fn from(value: Person) -> Self {
+        ValuePerson {
+            name: value.name,
+            age: value.age,
+            active: Some(value.active),
+        }
+    }
+}
+impl From<Person> for PersonId {
+    fn from(value: Person) -> Self {
+        PersonId { id: value.id }
+    }
+}
+impl From<PersonId> for Thing {
+    fn from(value: PersonId) -> Self {
+        Thing::from((PersonId::class_hash(), value.id))
+    }
+}
+#[async_trait]
+impl DbExtend<PersonEnumBase> for Person {
+    async fn db_extend<B: DbBackend>(self, _db: &B) -> DbResult<PersonEnumBase> {
+        Ok(PersonEnumBase {
+            name: self.name,
+            age: self.age,
+            active: self.active,
+        })
+    }
+}
+impl Entity for Person {
+    type Id = PersonId;
+    type Value = ValuePerson;
+    type Serializer = PersonSerializer;
+    type OrderBy = PersonOrderBy;
+    fn id(&self) -> PersonId {
+        PersonId {
+            id: self.id.clone(),
+        }
+    }
+}
+#[async_trait]
+impl Repository for Person {
+    async fn create<B: DbBackend>(db: &B, value: ValuePerson) -> DbResult<PersonId> {
+        value.db_create(db).await
+    }
+    async fn create_get<B: DbBackend>(db: &B, value: ValuePerson) -> DbResult<Person> {
+        value.db_create_get(db).await
+    }
+    async fn get<B: DbBackend>(db: &B, id: &PersonId) -> DbResult<Option<Person>> {
+        id.db_get(db).await
+    }
+    async fn update<B: DbBackend>(&mut self, db: &B) -> DbResult<Option<PersonId>> {
+        self.db_update(db).await
+    }
+    async fn delete<B: DbBackend>(db: &B, id: &PersonId) -> DbResult<Option<PersonId>> {
+        id.db_delete(db).await
+    }
+    async fn list(db: &Surreal<Any>, query: DbListQuery<PersonOrderBy>) -> DbResult<Page<Person>> {
+        Person::db_list(db, query).await
+    }
+    async fn resolve<B: DbBackend>(
+        serializer: PersonSerializer,
+        id: String,
+        db: &B,
+    ) -> DbResult<Option<Person>> {
+        serializer.db_resolve(id, db).await
+    }
+}
@@ -0,0 +1,154 @@
+// @generated by db-engine. Do not edit.
+use super::*;
+#[allow(unused_imports)]
+use super::{futures, serde, serde_json, surrealdb};
+/// Any kind of person.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+pub enum PersonEnum {
+    #[serde(rename = "6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d")]
+    Person(Person),
+    #[serde(rename = "b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d")]
+    User(User),
+    #[serde(rename = "5ed8944a85a9763fd315852f448cb7de36c5e928e13b3be427f98f7dc455f141")]
+    Guest(Guest),
+}
+impl From<Person> for PersonEnum {
+    fn from(value: Person) -> Self {
+        PersonEnum::Person(value)
+    }
+}
+impl From<User> for PersonEnum {
+    fn from(value: User) -> Self {
+        PersonEnum::User(value)
+    }
+}
+impl From<Guest> for PersonEnum {
+    fn from(value: Guest) -> Self {
+        PersonEnum::Guest(value)
+    }
+}
+/// Fields shared by all [`PersonEnum`] members.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "")]
+pub struct PersonEnumBase {
+    pub name: String,
+    /// Age in years.
+    pub age: u16,
+    pub active: bool,
+}
+#[async_trait]
+impl DbExtend<PersonEnumBase> for PersonEnum {
+    async fn db_extend<B: DbBackend>(self, db: &B) -> DbResult<PersonEnumBase> {
+        match self {
+            PersonEnum::Person(v) => v.db_extend(db).await,
+            PersonEnum::User(v) => v.db_extend(db).await,
+            PersonEnum::Guest(v) => v.db_extend(db).await,
+        }
+    }
+}
+/// Fields a list can be ordered by.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum PersonEnumOrderBy {
+    #[default]
+    Id,
+    Name,
+    Age,
+    Active,
+}
+impl DbOrderBy for PersonEnumOrderBy {
+    fn field(&self) -> &'static str {
+        match self {
+            PersonEnumOrderBy::Id => "id",
+            PersonEnumOrderBy::Name => "name",
+            PersonEnumOrderBy::Age => "age",
+            PersonEnumOrderBy::Active => "active",
+        }
+    }
+    fn surql_type(&self) -> &'static str {
+        match self {
+            PersonEnumOrderBy::Id => "record",
+            PersonEnumOrderBy::Name => "string",
+            PersonEnumOrderBy::Age => "int",
+            PersonEnumOrderBy::Active => "bool",
+        }
+    }
+}
+#[derive(Deserialize)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+enum PersonEnumRecord {
+    #[serde(rename = "6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d")]
+    Person(DbRecord<PersonSerializer>),
+    #[serde(rename = "b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d")]
+    User(DbRecord<UserSerializer>),
+    #[serde(rename = "5ed8944a85a9763fd315852f448cb7de36c5e928e13b3be427f98f7dc455f141")]
+    Guest(DbRecord<GuestSerializer>),
+}
+impl PersonEnum {
+    /// Fetches one page of records across all member tables.
+    pub async fn db_list(
+        db: &Surreal<Any>,
+        query: DbListQuery<PersonEnumOrderBy>,
+    ) -> DbResult<Page<PersonEnum>> {
+        let conditions = if query.with_deleted {
+            vec![]
+        } else {
+            vec![
+                "(deleted_at IS NONE OR meta::tb(id) NOTINSIDE [\"b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d\"])"
+                .to_string()
+            ]
+        };
+        let (records, has_more, total) = db_list_records::<_, PersonEnumRecord>(
+            db,
+            vec![
+                PersonId::class_hash(),
+                UserId::class_hash(),
+                GuestId::class_hash(),
+            ],
+            &query,
+            conditions,
+        )
+        .await?;
+        let next_cursor = match records.last() {
+            Some(PersonEnumRecord::Person(r)) => {
+                db_next_cursor(&query, has_more, PersonId::class_hash(), Some(r))
+            }
+            Some(PersonEnumRecord::User(r)) => {
+                db_next_cursor(&query, has_more, UserId::class_hash(), Some(r))
+            }
+            Some(PersonEnumRecord::Guest(r)) => {
+                db_next_cursor(&query, has_more, GuestId::class_hash(), Some(r))
+            }
+            None => None,
+        };
+        let items = join_all(records.into_iter().map(|record| async move {
+            DbResult::Ok(match record {
+                PersonEnumRecord::Person(r) => {
+                    r.value.db_resolve(r.id, db).await?.map(PersonEnum::Person)
+                }
+                PersonEnumRecord::User(r) => {
+                    r.value.db_resolve(r.id, db).await?.map(PersonEnum::User)
+                }
+                PersonEnumRecord::Guest(r) => {
+                    r.value.db_resolve(r.id, db).await?.map(PersonEnum::Guest)
+                }
+            })
+        }))
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+        Ok(Page {
+            items,
+            next_cursor,
+            total,
+            has_more,
+        })
+    }
+}
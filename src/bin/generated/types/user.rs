@@ -0,0 +1,488 @@
+// @generated by db-engine. Do not edit.
+use super::*;
+#[allow(unused_imports)]
+use super::{futures, serde, serde_json, surrealdb};
+pub(super) const DB_DEFINITIONS: &[&str] = &[
+    "DEFINE TABLE `b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d` SCHEMALESS;",
+    "DEFINE FIELD email ON TABLE `b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d` TYPE string;",
+    "DEFINE FIELD address ON TABLE `b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d` TYPE option<object>;",
+    "DEFINE FIELD address.street ON TABLE `b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d` TYPE option<string>;",
+    "DEFINE FIELD address.city ON TABLE `b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d` TYPE option<string>;",
+    "DEFINE FIELD status ON TABLE `b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d` TYPE string ASSERT $value INSIDE [\"active\", \"suspended\"];",
+    "DEFINE FIELD deleted_at ON TABLE `b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d` TYPE option<datetime>;",
+    "DEFINE FIELD PersonEnumBase ON TABLE `b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d` TYPE record<`6007db63e18e532c7399975ed77d2e3900810aa75cad165b8d2e5d8b08085c3d`>;",
+];
+/// Id of a [`User`] record.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d")]
+pub struct UserId {
+    #[serde(deserialize_with = "thing_to_string")]
+    pub id: String,
+}
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d")]
+pub struct User {
+    #[serde(deserialize_with = "thing_to_string")]
+    pub id: String,
+    pub email: String,
+    pub address: Option<Address>,
+    pub status: Status,
+    pub deleted_at: Option<surrealdb::sql::Datetime>,
+    #[serde(rename = "PersonEnumBase")]
+    pub person_enum_base: PersonId,
+}
+/// Values used to create a [`User`] record.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d")]
+pub struct ValueUser {
+    pub email: String,
+    pub address: Option<Address>,
+    pub status: Status,
+    #[serde(rename = "PersonEnumBase")]
+    #[serde(serialize_with = "db_link_to_thing")]
+    pub person_enum_base: DbLink<PersonId, ValuePerson>,
+}
+/// Stored form of a [`User`] record.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+#[serde(tag = "type")]
+#[serde(rename = "b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d")]
+pub struct UserSerializer {
+    pub email: String,
+    pub address: Option<Address>,
+    pub status: Status,
+    pub deleted_at: Option<surrealdb::sql::Datetime>,
+    #[serde(rename = "PersonEnumBase")]
+    pub person_enum_base: Thing,
+}
+impl ValueUser {
+    /// Validates the values and creates a new record, creating linked records first.
+    pub async fn db_create<B: DbBackend>(mut self, db: &B) -> DbResult<UserId> {
+        self.validate()?;
+        if let DbLink::New(n) = self.person_enum_base {
+            let result = Box::pin(n.db_create(db)).await?;
+            self.person_enum_base = DbLink::Existing(result);
+        }
+        let id = db
+            .create(UserId::class_hash(), UserId::computed_fields(), self)
+            .await?;
+        Ok(UserId { id })
+    }
+    /// Creates the record and fetches it back.
+    pub async fn db_create_get<B: DbBackend>(self, db: &B) -> DbResult<User> {
+        Ok(self.db_create(db).await?.db_get(db).await?.unwrap())
+    }
+    /// Checks the field rules.
+    pub fn validate(&self) -> Result<(), Vec<DbValidationError>> {
+        let mut errors = vec![];
+        {
+            let value = &self.email;
+            {
+                static PATTERN: std::sync::OnceLock<db_engine::runtime::v1::regex::Regex> =
+                    std::sync::OnceLock::new();
+                let pattern = PATTERN.get_or_init(|| {
+                    db_engine::runtime::v1::regex::Regex::new("^[^@]+@[^@]+$").unwrap()
+                });
+                if !pattern.is_match(value) {
+                    errors.push(DbValidationError {
+                        field: "email".to_string(),
+                        message: "must match ^[^@]+@[^@]+$".to_string(),
+                    });
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+impl User {
+    /// Validates the record and stores its current values.
+    pub async fn db_update<B: DbBackend>(&self, db: &B) -> DbResult<Option<UserId>> {
+        self.validate()?;
+        let updated = db
+            .update(
+                UserId::class_hash(),
+                &self.id,
+                UserId::computed_fields(),
+                ValueUser::from(self.clone()),
+                DbGuard {
+                    active: true,
+                    version: None,
+                },
+            )
+            .await?;
+        Ok(updated.map(|u| UserId { id: u.id }))
+    }
+    /// Stores the fields present in the patch, keeping the others, and fetches the
+    /// record back. Field rules are not checked for the patch.
+    pub async fn db_merge<B: DbBackend, P: Serialize + Send + 'static>(
+        &self,
+        db: &B,
+        patch: P,
+    ) -> DbResult<Option<User>> {
+        let Some(updated) = db
+            .merge(
+                UserId::class_hash(),
+                &self.id,
+                UserId::computed_fields(),
+                patch,
+                DbGuard {
+                    active: true,
+                    version: None,
+                },
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        UserId { id: updated.id }.db_get(db).await
+    }
+    /// Checks the field rules.
+    pub fn validate(&self) -> Result<(), Vec<DbValidationError>> {
+        let mut errors = vec![];
+        {
+            let value = &self.email;
+            {
+                static PATTERN: std::sync::OnceLock<db_engine::runtime::v1::regex::Regex> =
+                    std::sync::OnceLock::new();
+                let pattern = PATTERN.get_or_init(|| {
+                    db_engine::runtime::v1::regex::Regex::new("^[^@]+@[^@]+$").unwrap()
+                });
+                if !pattern.is_match(value) {
+                    errors.push(DbValidationError {
+                        field: "email".to_string(),
+                        message: "must match ^[^@]+@[^@]+$".to_string(),
+                    });
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+impl UserSerializer {
+    /// Resolves the links of a stored record.
+    ///
+    /// Prefetched links include soft-deleted records. A prefetched link to a
+    /// record that no longer exists makes the record unresolvable.
+    pub async fn db_resolve<B: DbBackend>(self, id: String, _db: &B) -> DbResult<Option<User>> {
+        let person_enum_base = PersonId {
+            id: self.person_enum_base.id.to_string(),
+        };
+        Ok(Some(User {
+            id,
+            person_enum_base,
+            email: self.email,
+            address: self.address,
+            status: self.status,
+            deleted_at: self.deleted_at,
+        }))
+    }
+}
+/// Fields a list can be ordered by.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum UserOrderBy {
+    #[default]
+    Id,
+    Email,
+    Status,
+}
+impl DbOrderBy for UserOrderBy {
+    fn field(&self) -> &'static str {
+        match self {
+            UserOrderBy::Id => "id",
+            UserOrderBy::Email => "email",
+            UserOrderBy::Status => "status",
+        }
+    }
+    fn surql_type(&self) -> &'static str {
+        match self {
+            UserOrderBy::Id => "record",
+            UserOrderBy::Email => "string",
+            UserOrderBy::Status => "string",
+        }
+    }
+}
+impl User {
+    /// Fetches one page of records.
+    pub async fn db_list(
+        db: &Surreal<Any>,
+        query: DbListQuery<UserOrderBy>,
+    ) -> DbResult<Page<User>> {
+        let conditions = if query.with_deleted {
+            vec![]
+        } else {
+            vec!["deleted_at IS NONE".to_string()]
+        };
+        let (records, has_more, total) = db_list_records::<_, DbRecord<UserSerializer>>(
+            db,
+            vec![UserId::class_hash()],
+            &query,
+            conditions,
+        )
+        .await?;
+        let next_cursor = db_next_cursor(&query, has_more, UserId::class_hash(), records.last());
+        let items = join_all(
+            records
+                .into_iter()
+                .map(|record| record.value.db_resolve(record.id, db)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        Ok(Page {
+            items,
+            next_cursor,
+            total,
+            has_more,
+        })
+    }
+    /// Streams all records, fetching them in batches. A `batch_size` of 0 is treated as 1.
+    pub fn db_stream(db: &Surreal<Any>, batch_size: u64) -> BoxStream<'_, DbResult<User>> {
+        let batch_size = batch_size.max(1);
+        futures::stream::try_unfold(Some(None), move |cursor| async move {
+            let Some(cursor) = cursor else {
+                return DbResult::Ok(None);
+            };
+            let page = User::db_list(db, DbListQuery::cursor(cursor, batch_size)).await?;
+            let items = futures::stream::iter(page.items.into_iter().map(Ok));
+            Ok(Some((items, page.next_cursor.map(Some))))
+        })
+        .try_flatten()
+        .boxed()
+    }
+}
+/// Fields an aggregation can be grouped by.
+#[derive(Debug, Clone, Copy)]
+pub enum UserGroupBy {
+    Email,
+    Status,
+}
+impl UserGroupBy {
+    fn field(&self) -> &'static str {
+        match *self {
+            UserGroupBy::Email => "email",
+            UserGroupBy::Status => "status",
+        }
+    }
+}
+/// Value of the group an aggregated row belongs to.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "db_engine::runtime::v1::serde")]
+pub enum UserGroupKey {
+    Email(String),
+    Status(Status),
+}
+/// Numeric fields an aggregation can summarize.
+#[derive(Debug, Clone, Copy)]
+pub enum UserMetric {}
+impl UserMetric {
+    fn field(&self) -> &'static str {
+        match *self {}
+    }
+}
+/// Builder for aggregation queries.
+#[derive(Debug, Clone, Default)]
+pub struct UserAggregate {
+    group_by: Option<UserGroupBy>,
+    metric: Option<UserMetric>,
+    with_deleted: bool,
+}
+impl UserAggregate {
+    /// Groups the rows by the given field.
+    pub fn group_by(mut self, group_by: UserGroupBy) -> Self {
+        self.group_by = Some(group_by);
+        self
+    }
+    /// Computes sum, average, minimum and maximum of the given field.
+    pub fn metric(mut self, metric: UserMetric) -> Self {
+        self.metric = Some(metric);
+        self
+    }
+    /// Includes soft-deleted records in the aggregation.
+    pub fn with_deleted(mut self) -> Self {
+        self.with_deleted = true;
+        self
+    }
+    /// Runs the aggregation.
+    pub async fn fetch(self, db: &Surreal<Any>) -> DbResult<Vec<DbAggregate<UserGroupKey>>> {
+        let mut fields = vec!["count() AS count".to_string()];
+        if let Some(metric) = self.metric {
+            let f = metric.field();
+            fields
+                .push(
+                    format!(
+                        "math::sum({f}) AS sum, math::mean({f}) AS avg, math::min({f}) AS min, math::max({f}) AS max"
+                    ),
+                );
+        }
+        let group = match self.group_by {
+            Some(group_by) => {
+                fields.insert(0, format!("{} AS key", group_by.field()));
+                "GROUP BY key"
+            }
+            None => "GROUP ALL",
+        };
+        let sql = format!(
+            "SELECT {} FROM type::table($table){} {}",
+            fields.join(", "),
+            if self.with_deleted {
+                ""
+            } else {
+                " WHERE deleted_at IS NONE"
+            },
+            group
+        );
+        let mut response = db.query(sql).bind(("table", UserId::class_hash())).await?;
+        Ok(match self.group_by {
+            None => response.take(0)?,
+            Some(UserGroupBy::Email) => response
+                .take::<Vec<DbAggregate<String>>>(0)?
+                .into_iter()
+                .map(|row| row.map_key(UserGroupKey::Email))
+                .collect(),
+            Some(UserGroupBy::Status) => response
+                .take::<Vec<DbAggregate<Status>>>(0)?
+                .into_iter()
+                .map(|row| row.map_key(UserGroupKey::Status))
+                .collect(),
+        })
+    }
+}
+impl User {
+    /// Starts an aggregation query.
+    pub fn db_aggregate() -> UserAggregate {
+        UserAggregate::default()
+    }
+}
+impl UserId {
+    /// Includes soft-deleted records in the following lookup.
+    pub fn with_deleted(&self) -> WithDeleted<UserId> {
+        WithDeleted(self.clone())
+    }
+    /// Fetches the record unless it was soft-deleted.
+    pub async fn db_get<B: DbBackend>(&self, db: &B) -> DbResult<Option<User>> {
+        Ok(self
+            .with_deleted()
+            .db_get(db)
+            .await?
+            .filter(|v| v.deleted_at.is_none()))
+    }
+}
+impl WithDeleted<UserId> {
+    /// Fetches the record, including a soft-deleted one.
+    pub async fn db_get<B: DbBackend>(&self, db: &B) -> DbResult<Option<User>> {
+        let Some(deserialized): Option<UserSerializer> =
+            db.select(UserId::class_hash(), &self.0.id).await?
+        else {
+            return Ok(None);
+        };
+        deserialized.db_resolve(self.0.id.clone(), db).await
+    }
+}
+impl UserId {
+    /// Marks the record as deleted without removing it.
+    pub async fn db_delete<B: DbBackend>(&self, db: &B) -> DbResult<Option<UserId>> {
+        let id = db
+            .soft_delete(UserId::class_hash(), &self.id, UserId::computed_fields())
+            .await?;
+        Ok(id.map(|id| UserId { id }))
+    }
+    /// Clears the deletion mark of a soft-deleted record.
+    pub async fn db_restore<B: DbBackend>(&self, db: &B) -> DbResult<Option<UserId>> {
+        let id = db
+            .restore(UserId::class_hash(), &self.id, UserId::computed_fields())
+            .await?;
+        Ok(id.map(|id| UserId { id }))
+    }
+}
+impl ClassHash for UserId {
+    fn class_hash() -> String {
+        "b512d97e7cbf97c273e4db073bbb547aa65a84589227f8f3d9e4a72b9372a24d".to_string()
+    }
+}
+impl From<User> for ValueUser {
+    fn from(value: User) -> Self {
+        ValueUser {
+            email: value.email,
+            address: value.address,
+            status: value.status,
+            person_enum_base: DbLink::Existing(value.person_enum_base),
+        }
+    }
+}
+impl From<User> for UserId {
+    fn from(value: User) -> Self {
+        UserId { id: value.id }
+    }
+}
+impl From<UserId> for Thing {
+    fn from(value: UserId) -> Self {
+        Thing::from((UserId::class_hash(), value.id))
+    }
+}
+#[async_trait]
+impl DbExtend<PersonEnumBase> for User {
+    async fn db_extend<B: DbBackend>(self, db: &B) -> DbResult<PersonEnumBase> {
+        let base = self.person_enum_base.db_get(db).await?.unwrap();
+        Ok(PersonEnumBase {
+            name: base.name,
+            age: base.age,
+            active: base.active,
+        })
+    }
+}
+impl Entity for User {
+    type Id = UserId;
+    type Value = ValueUser;
+    type Serializer = UserSerializer;
+    type OrderBy = UserOrderBy;
+    fn id(&self) -> UserId {
+        UserId {
+            id: self.id.clone(),
+        }
+    }
+}
+#[async_trait]
+impl Repository for User {
+    async fn create<B: DbBackend>(db: &B, value: ValueUser) -> DbResult<UserId> {
+        value.db_create(db).await
+    }
+    async fn create_get<B: DbBackend>(db: &B, value: ValueUser) -> DbResult<User> {
+        value.db_create_get(db).await
+    }
+    async fn get<B: DbBackend>(db: &B, id: &UserId) -> DbResult<Option<User>> {
+        id.db_get(db).await
+    }
+    async fn update<B: DbBackend>(&mut self, db: &B) -> DbResult<Option<UserId>> {
+        self.db_update(db).await
+    }
+    async fn delete<B: DbBackend>(db: &B, id: &UserId) -> DbResult<Option<UserId>> {
+        id.db_delete(db).await
+    }
+    async fn list(db: &Surreal<Any>, query: DbListQuery<UserOrderBy>) -> DbResult<Page<User>> {
+        User::db_list(db, query).await
+    }
+    async fn resolve<B: DbBackend>(
+        serializer: UserSerializer,
+        id: String,
+        db: &B,
+    ) -> DbResult<Option<User>> {
+        serializer.db_resolve(id, db).await
+    }
+}
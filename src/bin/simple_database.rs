@@ -29,6 +29,7 @@ struct Record {
 }
 
 #[tokio::main]
+#[allow(clippy::result_large_err)]
 async fn main() -> surrealdb::Result<()> {
     // Load envs
     let db_username = std::env::var("DB_USERNAME").unwrap();
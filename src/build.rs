@@ -0,0 +1,140 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use crate::db_manager::DbManager;
+use crate::db_validation::DbSchemaError;
+
+#[derive(Debug)]
+pub enum BuildError {
+    Schema(Vec<DbSchemaError>),
+    Syntax(syn::Error),
+    Io(PathBuf, std::io::Error),
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::Schema(errors) => {
+                writeln!(f, "invalid schema:")?;
+                let errors = errors
+                    .iter()
+                    .map(|e| format!("  {}", e))
+                    .collect::<Vec<_>>();
+                write!(f, "{}", errors.join("\n"))
+            }
+            BuildError::Syntax(e) => write!(f, "generated code is not valid Rust: {}", e),
+            BuildError::Io(path, e) => write!(f, "unable to write {}: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+pub fn to_code(schema: DbManager) -> Result<String, BuildError> {
+    unparse(schema.to_tokens().map_err(BuildError::Schema)?)
+}
+
+pub fn to_module_files(schema: DbManager) -> Result<Vec<(String, String)>, BuildError> {
+    schema
+        .to_modules()
+        .map_err(BuildError::Schema)?
+        .into_iter()
+        .map(|(name, tokens)| Ok((name, unparse(tokens)?)))
+        .collect()
+}
+
+/// First line of every generated file. Only files starting with it are treated as generated.
+pub const GENERATED_HEADER: &str = "// @generated by db-engine. Do not edit.";
+
+fn unparse(tokens: proc_macro2::TokenStream) -> Result<String, BuildError> {
+    let file = syn::parse2(tokens).map_err(BuildError::Syntax)?;
+    Ok(format!(
+        "{}\n{}",
+        GENERATED_HEADER,
+        prettyplease::unparse(&file)
+    ))
+}
+
+/// Makes Cargo rerun the build script only when one of the given files changes. List the build
+/// script itself and every file the schema is defined in; generation prints nothing on its own,
+/// so without this call Cargo reruns the script whenever any file of the package changes.
+pub fn rerun_if_changed<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) {
+    for path in paths {
+        println!("cargo:rerun-if-changed={}", path.as_ref().display());
+    }
+}
+
+fn resolve_out_path(out_path: impl AsRef<Path>) -> PathBuf {
+    match std::env::var_os("OUT_DIR") {
+        Some(dir) => PathBuf::from(dir).join(out_path),
+        None => out_path.as_ref().to_path_buf(),
+    }
+}
+
+fn write_file(path: &Path, code: String) -> Result<(), BuildError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| BuildError::Io(path.to_path_buf(), e))?;
+    }
+    std::fs::write(path, code).map_err(|e| BuildError::Io(path.to_path_buf(), e))
+}
+
+pub fn try_generate(schema: DbManager, out_path: impl AsRef<Path>) -> Result<PathBuf, BuildError> {
+    let path = resolve_out_path(out_path);
+    write_file(&path, to_code(schema)?)?;
+    Ok(path)
+}
+
+pub fn try_generate_modules(
+    schema: DbManager,
+    out_dir: impl AsRef<Path>,
+) -> Result<PathBuf, BuildError> {
+    let dir = resolve_out_path(out_dir);
+    let files = to_module_files(schema)?;
+    remove_stale_modules(&dir, &files)?;
+    for (name, code) in files {
+        write_file(&dir.join(name), code)?;
+    }
+    Ok(dir)
+}
+
+// Generated `.rs` files of removed classes go away; files without the header are left alone.
+fn remove_stale_modules(dir: &Path, files: &[(String, String)]) -> Result<(), BuildError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(BuildError::Io(dir.to_path_buf(), e)),
+    };
+    for entry in entries {
+        let path = entry
+            .map_err(|e| BuildError::Io(dir.to_path_buf(), e))?
+            .path();
+        let orphan = path.extension().is_some_and(|e| e == "rs")
+            && !files
+                .iter()
+                .any(|(name, _)| path.file_name().is_some_and(|f| f == name.as_str()));
+        if orphan && is_generated(&path)? {
+            std::fs::remove_file(&path).map_err(|e| BuildError::Io(path, e))?;
+        }
+    }
+    Ok(())
+}
+
+fn is_generated(path: &Path) -> Result<bool, BuildError> {
+    let code = std::fs::read(path).map_err(|e| BuildError::Io(path.to_path_buf(), e))?;
+    Ok(code.starts_with(GENERATED_HEADER.as_bytes()))
+}
+
+pub fn generate(schema: DbManager, out_path: impl AsRef<Path>) -> PathBuf {
+    try_generate(schema, out_path).unwrap_or_else(|e| fail(e))
+}
+
+pub fn generate_modules(schema: DbManager, out_dir: impl AsRef<Path>) -> PathBuf {
+    try_generate_modules(schema, out_dir).unwrap_or_else(|e| fail(e))
+}
+
+fn fail(e: BuildError) -> ! {
+    for line in e.to_string().lines() {
+        println!("cargo:warning=db-engine: {}", line);
+    }
+    panic!("db-engine code generation failed: {}", e);
+}
@@ -1,8 +1,12 @@
 use std::hash::Hash;
 use std::hash::Hasher;
 
-use crate::db_field::DbClassLinkSingle;
-use crate::db_field::{DbClassField, DbClassLinkMultiple, DbClassSimpleField};
+use crate::db_field::{DbClassField, DbClassLinkMultiple, DbClassSimpleField, DbFieldType};
+use crate::db_field::{DbClassLinkSingle, DbClassVectorField};
+
+pub(crate) const CREATED_AT_VALUE: &str = "$before OR time::now()";
+pub(crate) const UPDATED_AT_VALUE: &str = "time::now()";
+pub(crate) const VERSION_VALUE: &str = "($before OR 0) + 1";
 
 // #[derive(Eq, Hash, PartialEq, Debug, Clone)]
 // pub enum DbClassExtension {
@@ -13,19 +17,72 @@ use crate::db_field::{DbClassField, DbClassLinkMultiple, DbClassSimpleField};
 #[derive(Eq, Hash, PartialEq, Debug, Clone)]
 pub struct DbClassExtension(pub String, pub DbClass, pub bool);
 
+/// Generated type that extra derives and attributes are added to.
+///
+/// Derives are added verbatim, so every field of the type must support them: `Default` fails on
+/// types holding links, because `Thing` has no default, and `Eq` or `Hash` fail on types with
+/// `f32`/`f64` or vector fields.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
+pub enum DbStruct {
+    Main,
+    Id,
+    Value,
+    Serializer,
+    /// Enum over several classes.
+    Enum,
+    /// Embedded object.
+    Object,
+    /// Value enum.
+    ValueEnum,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DbClass {
     pub(crate) ident: DbClassIdentifier,
     pub(crate) extends: Vec<DbClassExtension>,
     pub(crate) fields: Vec<DbClassField>,
+    pub(crate) db_asserts: bool,
+    pub(crate) soft_delete: bool,
+    pub(crate) versioned: bool,
+    pub(crate) history: bool,
+    pub(crate) derives: Vec<(DbStruct, String)>,
+    pub(crate) attributes: Vec<(DbStruct, String)>,
+    pub(crate) doc: Option<String>,
 }
 
 impl DbClass {
+    /// Names of all types generated for the class.
+    pub(crate) fn generated_names(&self) -> Vec<String> {
+        let ident = &self.ident;
+        let mut names = vec![
+            ident.name.clone(),
+            ident.id_struct_name(),
+            ident.value_struct_name(),
+            ident.serializer_struct_name(),
+            ident.order_by_enum_name(),
+            ident.group_by_enum_name(),
+            ident.group_key_enum_name(),
+            ident.metric_enum_name(),
+            ident.aggregate_struct_name(),
+        ];
+        if self.history {
+            names.push(ident.history_struct_name());
+            names.push(ident.history_struct_name() + "Row");
+        }
+        names
+    }
     pub fn new(ident: DbClassIdentifier) -> Self {
         DbClass {
             ident,
             extends: vec![],
             fields: vec![],
+            db_asserts: false,
+            soft_delete: false,
+            versioned: false,
+            history: false,
+            derives: vec![],
+            attributes: vec![],
+            doc: None,
         }
     }
     pub fn with_name(name: &str) -> Self {
@@ -35,6 +92,64 @@ impl DbClass {
         self.fields.push(field);
         self
     }
+    pub fn with_doc(mut self, doc: &str) -> Self {
+        self.doc = Some(doc.to_string());
+        self
+    }
+    pub fn with_derive(mut self, target: DbStruct, derive: &str) -> Self {
+        self.derives.push((target, derive.to_string()));
+        self
+    }
+    pub fn with_attribute(mut self, target: DbStruct, attribute: &str) -> Self {
+        self.attributes.push((target, attribute.to_string()));
+        self
+    }
+    pub fn with_db_asserts(mut self) -> Self {
+        self.db_asserts = true;
+        self
+    }
+    pub fn with_timestamps(mut self) -> Self {
+        let timestamps = [
+            ("created_at", CREATED_AT_VALUE),
+            ("updated_at", UPDATED_AT_VALUE),
+        ];
+        for (name, value) in timestamps {
+            if self.simple_fields().iter().any(|f| f.name == name) {
+                continue;
+            }
+            self.fields.push(DbClassSimpleField::computed(
+                name,
+                DbFieldType::Datetime,
+                value,
+            ));
+        }
+        self
+    }
+    pub fn with_soft_delete(mut self) -> Self {
+        if !self.soft_delete {
+            self.soft_delete = true;
+            self.fields.push(DbClassSimpleField::readonly(
+                "deleted_at",
+                DbFieldType::option(DbFieldType::Datetime),
+            ));
+        }
+        self
+    }
+    pub fn with_version(mut self) -> Self {
+        if !self.versioned {
+            self.versioned = true;
+            self.fields.push(DbClassSimpleField::computed(
+                "version",
+                DbFieldType::U64,
+                VERSION_VALUE,
+            ));
+        }
+        self
+    }
+    pub fn with_history(mut self) -> Self {
+        self.history = true;
+        self
+    }
     pub fn extends(&mut self, ext: DbClassExtension) {
         if !ext.2 {
             self.fields
@@ -82,6 +197,18 @@ impl DbClass {
             })
             .collect()
     }
+    pub fn vector_fields(&self) -> Vec<DbClassVectorField> {
+        self.fields
+            .iter()
+            .filter_map(|f| {
+                if let DbClassField::Vector(i) = f {
+                    Some(i.clone())
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -106,6 +233,33 @@ impl DbClassIdentifier {
     pub fn serializer_struct_name(&self) -> String {
         self.name.clone() + "Serializer"
     }
+    pub fn table_name(&self) -> String {
+        format!("`{}`", self.hash)
+    }
+    pub fn order_by_enum_name(&self) -> String {
+        self.name.clone() + "OrderBy"
+    }
+    pub fn group_by_enum_name(&self) -> String {
+        self.name.clone() + "GroupBy"
+    }
+    pub fn group_key_enum_name(&self) -> String {
+        self.name.clone() + "GroupKey"
+    }
+    pub fn metric_enum_name(&self) -> String {
+        self.name.clone() + "Metric"
+    }
+    pub fn aggregate_struct_name(&self) -> String {
+        self.name.clone() + "Aggregate"
+    }
+    pub fn history_struct_name(&self) -> String {
+        self.name.clone() + "History"
+    }
+    pub fn history_table_hash(&self) -> String {
+        format!("{}_history", self.hash)
+    }
+    pub fn history_table_name(&self) -> String {
+        format!("`{}`", self.history_table_hash())
+    }
 }
 
 impl Hash for DbClassIdentifier {
@@ -2,15 +2,28 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
 use crate::{
-    db_class::{DbClass, DbClassIdentifier},
-    syntax::struct_builder::{Field, StructSyntaxBuilder},
+    db_class::{DbClass, DbClassIdentifier, DbStruct},
+    syntax::{
+        ddl_builder::surql_string,
+        doc_tokens,
+        impl_builder::order_by_tokens,
+        serde_crate_tokens,
+        struct_builder::{Field, StructSyntaxBuilder},
+        struct_config_tokens,
+    },
 };
 
 #[derive(Eq, Hash, PartialEq, Debug, Clone)]
 pub struct DbEnum {
-    name: String,
-    idents: Vec<DbClassIdentifier>,
+    pub(crate) name: String,
+    pub(crate) idents: Vec<DbClassIdentifier>,
     base: DbClass,
+    /// Hashes of the member tables that use soft deletes.
+    pub(crate) soft_deleted: Vec<String>,
+    pub(crate) doc: Option<String>,
+    pub(crate) extended: Vec<String>,
+    pub(crate) derives: Vec<(DbStruct, String)>,
+    pub(crate) attributes: Vec<(DbStruct, String)>,
 }
 
 impl DbEnum {
@@ -18,17 +31,43 @@ impl DbEnum {
         DbEnum {
             name: name.into(),
             idents: vec![base.ident.clone()],
+            soft_deleted: if base.soft_delete {
+                vec![base.ident.hash.clone()]
+            } else {
+                vec![]
+            },
             base: base.clone(),
+            doc: None,
+            extended: vec![],
+            derives: vec![],
+            attributes: vec![],
         }
     }
 
+    /// Names of all types generated for the enum.
+    pub(crate) fn generated_names(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.base_name_inner(),
+            format!("{}Record", self.name),
+            format!("{}OrderBy", self.name),
+        ]
+    }
+
     pub fn add_type(&mut self, ident: &DbClassIdentifier) {
         self.idents.push(ident.clone())
     }
 
     pub fn to_tokens(&self) -> TokenStream {
+        let serde_crate = serde_crate_tokens();
         let name = format_ident!("{}", self.name);
-        let value_name = format_ident!("{}", self.base_name_inner());
+        let doc = doc_tokens(&self.doc);
+        let (derives, attributes) = struct_config_tokens(
+            &self.derives,
+            &self.attributes,
+            DbStruct::Enum,
+            &["Debug", "Serialize", "Deserialize", "Clone"],
+        );
         let variants: Vec<_> = self
             .idents
             .iter()
@@ -47,23 +86,23 @@ impl DbEnum {
             .map(|i| {
                 let variant = format_ident!("{}", i.name);
                 quote! {
-                    impl Into<#name> for #variant {
-                        fn into(self) -> #name {
-                            #name::#variant(self)
+                    impl From<#variant> for #name {
+                        fn from(value: #variant) -> Self {
+                            #name::#variant(value)
                         }
                     }
                 }
             })
             .collect();
-        let variant_names: Vec<_> = self
-            .idents
-            .iter()
-            .map(|i| format_ident!("{}", i.name))
-            .collect();
         let value_struct = self.value_struct().to_tokens();
+        let list_tokens = self.list_tokens();
+        let extend_tokens = self.extend_tokens();
         quote! {
-            #[derive(Debug, Serialize, Deserialize, Clone)]
+            #doc
+            #[derive(Debug, Serialize, Deserialize, Clone #(, #derives)*)]
+            #serde_crate
             #[serde(tag = "type")]
+            #(#attributes)*
             pub enum #name {
                 #(#variants,)*
             }
@@ -72,9 +111,30 @@ impl DbEnum {
 
             #value_struct
 
+            #extend_tokens
+
+            #list_tokens
+        }
+    }
+
+    fn extend_tokens(&self) -> TokenStream {
+        if !self.idents[1..]
+            .iter()
+            .all(|i| self.extended.contains(&i.hash))
+        {
+            return quote! {};
+        }
+        let name = format_ident!("{}", self.name);
+        let value_name = format_ident!("{}", self.base_name_inner());
+        let variant_names: Vec<_> = self
+            .idents
+            .iter()
+            .map(|i| format_ident!("{}", i.name))
+            .collect();
+        quote! {
             #[async_trait]
             impl DbExtend<#value_name> for #name {
-                async fn db_extend(self, db: &Surreal<Client>) -> surrealdb::Result<#value_name>{
+                async fn db_extend<B: DbBackend>(self, db: &B) -> DbResult<#value_name>{
                     match self {
                         #(#name::#variant_names(v) => v.db_extend(db).await,)*
                     }
@@ -83,10 +143,117 @@ impl DbEnum {
         }
     }
 
+    fn list_tokens(&self) -> TokenStream {
+        let serde_crate = serde_crate_tokens();
+        let name = format_ident!("{}", self.name);
+        let record_name = format_ident!("{}Record", self.name);
+        let variant_names: Vec<_> = self
+            .idents
+            .iter()
+            .map(|i| format_ident!("{}", i.name))
+            .collect();
+        let hashes: Vec<_> = self.idents.iter().map(|i| &i.hash).collect();
+        let serializer_names: Vec<_> = self
+            .idents
+            .iter()
+            .map(|i| format_ident!("{}", i.serializer_struct_name()))
+            .collect();
+        let id_names: Vec<_> = self
+            .idents
+            .iter()
+            .map(|i| format_ident!("{}", i.id_struct_name()))
+            .collect();
+        let order_by_name = format!("{}OrderBy", self.name);
+        let order_by_iden = format_ident!("{}", order_by_name);
+        let order_by_tokens = order_by_tokens(&order_by_name, self.base.simple_fields());
+        let conditions = if self.soft_deleted.is_empty() {
+            quote! { vec![] }
+        } else {
+            let tables = self
+                .soft_deleted
+                .iter()
+                .map(|h| surql_string(h))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let condition = format!(
+                "(deleted_at IS NONE OR meta::tb(id) NOTINSIDE [{}])",
+                tables
+            );
+            quote! {
+                if query.with_deleted {
+                    vec![]
+                } else {
+                    vec![#condition.to_string()]
+                }
+            }
+        };
+        quote! {
+            #order_by_tokens
+
+            #[derive(Deserialize)]
+            #serde_crate
+            #[serde(tag = "type")]
+            enum #record_name {
+                #(
+                    #[serde(rename = #hashes)]
+                    #variant_names(DbRecord<#serializer_names>),
+                )*
+            }
+
+            impl #name {
+                /// Fetches one page of records across all member tables.
+                pub async fn db_list(db: &Surreal<Any>, query: DbListQuery<#order_by_iden>) -> DbResult<Page<#name>> {
+                    let conditions = #conditions;
+                    let (records, has_more, total) = db_list_records::<_, #record_name>(
+                        db,
+                        vec![#(#id_names::class_hash(),)*],
+                        &query,
+                        conditions,
+                    )
+                    .await?;
+                    let next_cursor = match records.last() {
+                        #(Some(#record_name::#variant_names(r)) => {
+                            db_next_cursor(&query, has_more, #id_names::class_hash(), Some(r))
+                        })*
+                        None => None,
+                    };
+                    let items = join_all(records.into_iter().map(|record| async move {
+                        DbResult::Ok(match record {
+                            #(#record_name::#variant_names(r) => r
+                                .value
+                                .db_resolve(r.id, db)
+                                .await?
+                                .map(#name::#variant_names),)*
+                        })
+                    }))
+                    .await
+                    .into_iter()
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                    Ok(Page {
+                        items,
+                        next_cursor,
+                        total,
+                        has_more,
+                    })
+                }
+            }
+        }
+    }
+
     fn value_struct(&self) -> StructSyntaxBuilder {
         let mut value_struct = StructSyntaxBuilder::new(self.base_name_inner(), "");
+        value_struct.with_doc(Some(format!(
+            "Fields shared by all [`{}`] members.",
+            self.name
+        )));
         for f in self.base.simple_fields() {
-            value_struct.add_field(Field::new(f.name, f.type_));
+            value_struct.add_field(
+                Field::with_decorators(f.name, f.type_.rust_type(), f.type_.serde_decorators())
+                    .with_doc(f.doc.clone()),
+            );
         }
         value_struct
     }
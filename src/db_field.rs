@@ -1,31 +1,434 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, ToTokens};
+
 use crate::db_class::DbClassIdentifier;
+use crate::db_object::DbObjectIdentifier;
+use crate::db_value_enum::DbValueEnumIdentifier;
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+pub enum DbFieldRule {
+    Min(String),
+    Max(String),
+    MinLength(usize),
+    MaxLength(usize),
+    Regex(String),
+    OneOf(Vec<String>),
+    Custom(String),
+}
+
+impl DbFieldRule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DbFieldRule::Min(_) => "min",
+            DbFieldRule::Max(_) => "max",
+            DbFieldRule::MinLength(_) => "min_length",
+            DbFieldRule::MaxLength(_) => "max_length",
+            DbFieldRule::Regex(_) => "regex",
+            DbFieldRule::OneOf(_) => "one_of",
+            DbFieldRule::Custom(_) => "custom",
+        }
+    }
+    pub fn surql_assert(&self) -> Option<String> {
+        match self {
+            DbFieldRule::Min(v) => Some(format!("$value >= {}", v)),
+            DbFieldRule::Max(v) => Some(format!("$value <= {}", v)),
+            DbFieldRule::MinLength(v) => Some(format!("string::len($value) >= {}", v)),
+            DbFieldRule::MaxLength(v) => Some(format!("string::len($value) <= {}", v)),
+            DbFieldRule::Regex(v) => Some(format!("$value = /{}/", v.replace('/', "\\/"))),
+            DbFieldRule::OneOf(v) => Some(format!("$value INSIDE [{}]", v.join(", "))),
+            DbFieldRule::Custom(_) => None,
+        }
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+pub enum DbFieldDefault {
+    Literal(String),
+    Rust(String),
+    Surql(String),
+}
+
+impl DbFieldDefault {
+    pub fn surql_default(&self) -> Option<String> {
+        match self {
+            DbFieldDefault::Literal(v) | DbFieldDefault::Surql(v) => Some(v.clone()),
+            DbFieldDefault::Rust(_) => None,
+        }
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+pub enum DbFieldType {
+    String,
+    Char,
+    I8,
+    I16,
+    I32,
+    I64,
+    /// Stored as a SurrealDB `int`, so values must fit in 64 bits.
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    /// Stored as a SurrealDB `int`, so values must fit in 64 bits.
+    U128,
+    Usize,
+    F32,
+    F64,
+    Decimal,
+    Bool,
+    Datetime,
+    Duration,
+    Uuid,
+    Bytes,
+    Geometry,
+    Object,
+    Array(Box<DbFieldType>),
+    Map(Box<DbFieldType>),
+    Option(Box<DbFieldType>),
+    Embedded(DbObjectIdentifier),
+    ValueEnum(DbValueEnumIdentifier),
+    Custom {
+        rust: String,
+        surql: Option<String>,
+    },
+}
+
+impl DbFieldType {
+    pub fn array(inner: DbFieldType) -> DbFieldType {
+        DbFieldType::Array(Box::new(inner))
+    }
+    pub fn map(inner: DbFieldType) -> DbFieldType {
+        DbFieldType::Map(Box::new(inner))
+    }
+    pub fn option(inner: DbFieldType) -> DbFieldType {
+        DbFieldType::Option(Box::new(inner))
+    }
+    pub fn embedded(ident: &DbObjectIdentifier) -> DbFieldType {
+        DbFieldType::Embedded(ident.clone())
+    }
+    pub fn value_enum(ident: &DbValueEnumIdentifier) -> DbFieldType {
+        DbFieldType::ValueEnum(ident.clone())
+    }
+    pub fn custom(rust: &str) -> DbFieldType {
+        DbFieldType::Custom {
+            rust: rust.to_string(),
+            surql: None,
+        }
+    }
+    pub fn custom_with_surql(rust: &str, surql: &str) -> DbFieldType {
+        DbFieldType::Custom {
+            rust: rust.to_string(),
+            surql: Some(surql.to_string()),
+        }
+    }
+    pub fn rust_type(&self) -> String {
+        match self {
+            DbFieldType::String => "String".to_string(),
+            DbFieldType::Char => "char".to_string(),
+            DbFieldType::I8 => "i8".to_string(),
+            DbFieldType::I16 => "i16".to_string(),
+            DbFieldType::I32 => "i32".to_string(),
+            DbFieldType::I64 => "i64".to_string(),
+            DbFieldType::I128 => "i128".to_string(),
+            DbFieldType::U8 => "u8".to_string(),
+            DbFieldType::U16 => "u16".to_string(),
+            DbFieldType::U32 => "u32".to_string(),
+            DbFieldType::U64 => "u64".to_string(),
+            DbFieldType::U128 => "u128".to_string(),
+            DbFieldType::Usize => "usize".to_string(),
+            DbFieldType::F32 => "f32".to_string(),
+            DbFieldType::F64 => "f64".to_string(),
+            DbFieldType::Decimal => "surrealdb::sql::Number".to_string(),
+            DbFieldType::Bool => "bool".to_string(),
+            DbFieldType::Datetime => "surrealdb::sql::Datetime".to_string(),
+            DbFieldType::Duration => "surrealdb::sql::Duration".to_string(),
+            DbFieldType::Uuid => "surrealdb::sql::Uuid".to_string(),
+            DbFieldType::Bytes => "surrealdb::sql::Bytes".to_string(),
+            DbFieldType::Geometry => "surrealdb::sql::Geometry".to_string(),
+            DbFieldType::Object => "serde_json::Map<String, serde_json::Value>".to_string(),
+            DbFieldType::Array(t) => format!("Vec<{}>", t.rust_type()),
+            DbFieldType::Map(t) => {
+                format!("std::collections::HashMap<String, {}>", t.rust_type())
+            }
+            DbFieldType::Option(t) => format!("Option<{}>", t.rust_type()),
+            DbFieldType::Embedded(ident) => ident.name.clone(),
+            DbFieldType::ValueEnum(ident) => ident.name.clone(),
+            DbFieldType::Custom { rust, .. } => rust.clone(),
+        }
+    }
+    /// Serde attributes the generated field needs for this type.
+    pub fn serde_decorators(&self) -> Vec<String> {
+        if self.is_wide_int() {
+            vec![r#"#[serde(with = "db_engine::runtime::v1::wide_int")]"#.to_string()]
+        } else {
+            vec![]
+        }
+    }
+    fn is_wide_int(&self) -> bool {
+        match self {
+            DbFieldType::I128 | DbFieldType::U128 => true,
+            DbFieldType::Array(t) | DbFieldType::Map(t) | DbFieldType::Option(t) => t.is_wide_int(),
+            _ => false,
+        }
+    }
+    pub fn surql_type(&self) -> Option<String> {
+        let surql = match self {
+            DbFieldType::ValueEnum(ident) if ident.is_int() => "int".to_string(),
+            DbFieldType::String | DbFieldType::Char | DbFieldType::ValueEnum(_) => {
+                "string".to_string()
+            }
+            DbFieldType::I8
+            | DbFieldType::I16
+            | DbFieldType::I32
+            | DbFieldType::I64
+            | DbFieldType::I128
+            | DbFieldType::U8
+            | DbFieldType::U16
+            | DbFieldType::U32
+            | DbFieldType::U64
+            | DbFieldType::U128
+            | DbFieldType::Usize => "int".to_string(),
+            DbFieldType::F32 | DbFieldType::F64 => "float".to_string(),
+            DbFieldType::Decimal => "decimal".to_string(),
+            DbFieldType::Bool => "bool".to_string(),
+            DbFieldType::Datetime => "datetime".to_string(),
+            DbFieldType::Duration => "duration".to_string(),
+            DbFieldType::Uuid => "uuid".to_string(),
+            DbFieldType::Bytes => "bytes".to_string(),
+            DbFieldType::Geometry => "geometry<feature>".to_string(),
+            DbFieldType::Object | DbFieldType::Map(_) | DbFieldType::Embedded(_) => {
+                "object".to_string()
+            }
+            DbFieldType::Array(t) => match t.surql_type() {
+                Some(inner) => format!("array<{}>", inner),
+                None => "array".to_string(),
+            },
+            DbFieldType::Option(t) => format!("option<{}>", t.surql_type()?),
+            DbFieldType::Custom { surql, .. } => surql.clone()?,
+        };
+        Some(surql)
+    }
+    pub fn surql_assert(&self) -> Option<String> {
+        match self {
+            DbFieldType::ValueEnum(ident) => {
+                Some(format!("$value INSIDE {}", ident.surql_values()))
+            }
+            DbFieldType::Array(t) => match t.as_ref() {
+                DbFieldType::ValueEnum(ident) => {
+                    Some(format!("$value ALLINSIDE {}", ident.surql_values()))
+                }
+                _ => None,
+            },
+            DbFieldType::Option(t) => t
+                .surql_assert()
+                .map(|a| format!("($value = NONE OR {})", a)),
+            _ => None,
+        }
+    }
+    pub fn embedded_path(&self) -> Option<(&DbObjectIdentifier, String, bool)> {
+        match self {
+            DbFieldType::Embedded(ident) => Some((ident, String::new(), false)),
+            DbFieldType::Array(t) => t
+                .embedded_path()
+                .map(|(ident, path, optional)| (ident, format!(".*{}", path), optional)),
+            DbFieldType::Option(t) => t
+                .embedded_path()
+                .map(|(ident, path, _)| (ident, path, true)),
+            _ => None,
+        }
+    }
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            DbFieldType::I8
+                | DbFieldType::I16
+                | DbFieldType::I32
+                | DbFieldType::I64
+                | DbFieldType::I128
+                | DbFieldType::U8
+                | DbFieldType::U16
+                | DbFieldType::U32
+                | DbFieldType::U64
+                | DbFieldType::U128
+                | DbFieldType::Usize
+        )
+    }
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || matches!(self, DbFieldType::F32 | DbFieldType::F64)
+    }
+    /// Parses a rule or default literal as a value of this type, returning it as a Rust
+    /// literal of the type. Only strings, chars, bools and numbers have literals.
+    pub(crate) fn literal_tokens(&self, literal: &str) -> Option<TokenStream> {
+        let literal = literal.trim();
+        let signed = |negative: bool, lit: Literal| {
+            if negative {
+                quote! { -#lit }
+            } else {
+                lit.into_token_stream()
+            }
+        };
+        match self {
+            DbFieldType::String => Some(
+                syn::parse_str::<syn::LitStr>(literal)
+                    .ok()?
+                    .token()
+                    .into_token_stream(),
+            ),
+            DbFieldType::Char => Some(
+                syn::parse_str::<syn::LitChar>(literal)
+                    .ok()?
+                    .token()
+                    .into_token_stream(),
+            ),
+            DbFieldType::Bool => {
+                let value = syn::parse_str::<syn::LitBool>(literal).ok()?.value;
+                Some(quote! { #value })
+            }
+            DbFieldType::F32 | DbFieldType::F64 => {
+                let value = literal.parse::<f64>().ok().filter(|v| v.is_finite())?;
+                if *self == DbFieldType::F32 && value.abs() > f32::MAX as f64 {
+                    return None;
+                }
+                Some(signed(value < 0.0, Literal::f64_unsuffixed(value.abs())))
+            }
+            t if t.is_integer() => {
+                let value = literal.parse::<i128>().ok()?;
+                let (min, max) = match t {
+                    DbFieldType::I8 => (i8::MIN as i128, i8::MAX as i128),
+                    DbFieldType::I16 => (i16::MIN as i128, i16::MAX as i128),
+                    DbFieldType::I32 => (i32::MIN as i128, i32::MAX as i128),
+                    DbFieldType::I64 | DbFieldType::I128 => (i64::MIN as i128, i64::MAX as i128),
+                    DbFieldType::U8 => (0, u8::MAX as i128),
+                    DbFieldType::U16 => (0, u16::MAX as i128),
+                    DbFieldType::U32 => (0, u32::MAX as i128),
+                    _ => (0, u64::MAX as i128),
+                };
+                (min..=max)
+                    .contains(&value)
+                    .then(|| signed(value < 0, Literal::u128_unsuffixed(value.unsigned_abs())))
+            }
+            _ => None,
+        }
+    }
+    pub fn is_groupable(&self) -> bool {
+        self.is_integer()
+            || matches!(
+                self,
+                DbFieldType::String
+                    | DbFieldType::Char
+                    | DbFieldType::Bool
+                    | DbFieldType::ValueEnum(_)
+            )
+    }
+    pub fn is_orderable(&self) -> bool {
+        self.is_numeric()
+            || matches!(
+                self,
+                DbFieldType::String
+                    | DbFieldType::Char
+                    | DbFieldType::Decimal
+                    | DbFieldType::Bool
+                    | DbFieldType::Datetime
+                    | DbFieldType::Duration
+                    | DbFieldType::Uuid
+                    | DbFieldType::ValueEnum(_)
+            )
+    }
+}
 
 #[derive(Eq, Hash, PartialEq, Debug, Clone)]
 pub struct DbClassSimpleField {
     pub name: String,
-    pub type_: String,
+    pub doc: Option<String>,
+    pub type_: DbFieldType,
+    pub rules: Vec<DbFieldRule>,
+    pub default: Option<DbFieldDefault>,
+    pub value: Option<String>,
+    pub readonly: bool,
 }
 
 impl DbClassSimpleField {
-    pub fn new(name: &str, type_: &str) -> DbClassField {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(name: &str, type_: DbFieldType) -> DbClassField {
+        DbClassSimpleField::with_rules(name, type_, vec![])
+    }
+    pub fn with_rules(name: &str, type_: DbFieldType, rules: Vec<DbFieldRule>) -> DbClassField {
+        DbClassSimpleField {
+            name: name.to_string(),
+            doc: None,
+            type_,
+            rules,
+            default: None,
+            value: None,
+            readonly: false,
+        }
+        .into()
+    }
+    pub fn with_default(name: &str, type_: DbFieldType, default: DbFieldDefault) -> DbClassField {
+        DbClassSimpleField {
+            name: name.to_string(),
+            doc: None,
+            type_,
+            rules: vec![],
+            default: Some(default),
+            value: None,
+            readonly: false,
+        }
+        .into()
+    }
+    pub fn computed(name: &str, type_: DbFieldType, value: &str) -> DbClassField {
         DbClassSimpleField {
             name: name.to_string(),
-            type_: type_.to_string(),
+            doc: None,
+            type_,
+            rules: vec![],
+            default: None,
+            value: Some(value.to_string()),
+            readonly: true,
         }
         .into()
     }
+    pub fn readonly(name: &str, type_: DbFieldType) -> DbClassField {
+        DbClassSimpleField {
+            name: name.to_string(),
+            doc: None,
+            type_,
+            rules: vec![],
+            default: None,
+            value: None,
+            readonly: true,
+        }
+        .into()
+    }
+    pub fn is_readonly(&self) -> bool {
+        self.readonly
+    }
+    pub fn is_numeric(&self) -> bool {
+        self.type_.is_numeric()
+    }
+    pub fn is_groupable(&self) -> bool {
+        self.type_.is_groupable()
+    }
+    pub fn is_orderable(&self) -> bool {
+        self.type_.is_orderable()
+    }
 }
 #[derive(Eq, Hash, PartialEq, Debug, Clone)]
 pub struct DbClassLinkMultiple {
     pub name: String,
+    pub doc: Option<String>,
     pub ident: DbClassIdentifier,
     pub prefetch: bool,
 }
 
 impl DbClassLinkMultiple {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(name: &str, ident: &DbClassIdentifier) -> DbClassField {
         DbClassLinkMultiple {
             name: name.to_string(),
+            doc: None,
             ident: ident.clone(),
             prefetch: false,
         }
@@ -34,6 +437,7 @@ impl DbClassLinkMultiple {
     pub fn new_prefetch(name: &str, ident: &DbClassIdentifier) -> DbClassField {
         DbClassLinkMultiple {
             name: name.to_string(),
+            doc: None,
             ident: ident.clone(),
             prefetch: true,
         }
@@ -43,27 +447,88 @@ impl DbClassLinkMultiple {
 #[derive(Eq, Hash, PartialEq, Debug, Clone)]
 pub struct DbClassLinkSingle {
     pub name: String,
+    pub doc: Option<String>,
     pub ident: DbClassIdentifier,
     pub prefetch: bool,
+    pub(crate) boxed: bool,
 }
 
 impl DbClassLinkSingle {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(name: &str, ident: &DbClassIdentifier) -> DbClassField {
         DbClassLinkSingle {
             name: name.to_string(),
+            doc: None,
             ident: ident.clone(),
             prefetch: false,
+            boxed: false,
         }
         .into()
     }
     pub fn new_prefetch(name: &str, ident: &DbClassIdentifier) -> DbClassField {
         DbClassLinkSingle {
             name: name.to_string(),
+            doc: None,
             ident: ident.clone(),
             prefetch: true,
+            boxed: false,
+        }
+        .into()
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
+pub enum DbVectorDistance {
+    Euclidean,
+    Cosine,
+    Manhattan,
+}
+
+impl DbVectorDistance {
+    pub fn surql_name(&self) -> &'static str {
+        match self {
+            DbVectorDistance::Euclidean => "EUCLIDEAN",
+            DbVectorDistance::Cosine => "COSINE",
+            DbVectorDistance::Manhattan => "MANHATTAN",
+        }
+    }
+    pub fn surql_distance(&self, field: &str, param: &str) -> String {
+        match self {
+            DbVectorDistance::Euclidean => {
+                format!("vector::distance::euclidean({}, {})", field, param)
+            }
+            DbVectorDistance::Cosine => {
+                format!("1 - vector::similarity::cosine({}, {})", field, param)
+            }
+            DbVectorDistance::Manhattan => {
+                format!("vector::distance::manhattan({}, {})", field, param)
+            }
+        }
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+pub struct DbClassVectorField {
+    pub name: String,
+    pub doc: Option<String>,
+    pub dimension: usize,
+    pub distance: DbVectorDistance,
+}
+
+impl DbClassVectorField {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(name: &str, dimension: usize, distance: DbVectorDistance) -> DbClassField {
+        DbClassVectorField {
+            name: name.to_string(),
+            doc: None,
+            dimension,
+            distance,
         }
         .into()
     }
+    pub fn index_name(&self) -> String {
+        format!("{}_index", self.name)
+    }
 }
 
 #[derive(Eq, Hash, PartialEq, Debug, Clone)]
@@ -71,6 +536,20 @@ pub enum DbClassField {
     Simple(DbClassSimpleField),
     LinkSingle(DbClassLinkSingle),
     LinkMultiple(DbClassLinkMultiple),
+    Vector(DbClassVectorField),
+}
+
+impl DbClassField {
+    pub fn with_doc(mut self, doc: &str) -> Self {
+        let target = match &mut self {
+            DbClassField::Simple(f) => &mut f.doc,
+            DbClassField::LinkSingle(f) => &mut f.doc,
+            DbClassField::LinkMultiple(f) => &mut f.doc,
+            DbClassField::Vector(f) => &mut f.doc,
+        };
+        *target = Some(doc.to_string());
+        self
+    }
 }
 
 impl From<DbClassSimpleField> for DbClassField {
@@ -90,3 +569,9 @@ impl From<DbClassLinkMultiple> for DbClassField {
         DbClassField::LinkMultiple(value)
     }
 }
+
+impl From<DbClassVectorField> for DbClassField {
+    fn from(value: DbClassVectorField) -> Self {
+        DbClassField::Vector(value)
+    }
+}
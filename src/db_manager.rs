@@ -1,14 +1,31 @@
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 
 use crate::{
-    db_class::{DbClass, DbClassExtension, DbClassIdentifier},
+    db_class::{DbClass, DbClassExtension, DbClassIdentifier, DbStruct},
     db_enum::DbEnum,
+    db_field::DbClassField,
+    db_object::{DbObject, DbObjectIdentifier},
+    db_validation::{DbSchemaError, DbSchemaErrorKind},
+    db_value_enum::{DbValueEnum, DbValueEnumIdentifier},
+    syntax::to_snake_case,
 };
 
 pub struct DbManager {
-    classes: Vec<DbClass>,
-    enums: Vec<DbEnum>,
+    pub(crate) classes: Vec<DbClass>,
+    pub(crate) enums: Vec<DbEnum>,
+    pub(crate) objects: Vec<DbObject>,
+    pub(crate) value_enums: Vec<DbValueEnum>,
+    timestamps: bool,
+    pub(crate) derives: Vec<(DbStruct, String)>,
+    pub(crate) attributes: Vec<(DbStruct, String)>,
+    pub(crate) errors: Vec<DbSchemaError>,
+}
+
+impl Default for DbManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl DbManager {
@@ -16,15 +33,45 @@ impl DbManager {
         DbManager {
             classes: vec![],
             enums: vec![],
+            objects: vec![],
+            value_enums: vec![],
+            timestamps: false,
+            derives: vec![],
+            attributes: vec![],
+            errors: vec![],
         }
     }
 
+    pub fn enable_timestamps(&mut self) {
+        self.timestamps = true;
+    }
+
+    pub fn add_derive(&mut self, target: DbStruct, derive: &str) {
+        self.derives.push((target, derive.to_string()));
+    }
+
+    pub fn add_attribute(&mut self, target: DbStruct, attribute: &str) {
+        self.attributes.push((target, attribute.to_string()));
+    }
+
     pub fn add_class(&mut self, class: DbClass) -> DbClassIdentifier {
         let ident = class.ident.clone();
         self.classes.push(class);
         ident
     }
 
+    pub fn add_object(&mut self, object: DbObject) -> DbObjectIdentifier {
+        let ident = object.ident();
+        self.objects.push(object);
+        ident
+    }
+
+    pub fn add_value_enum(&mut self, value_enum: DbValueEnum) -> DbValueEnumIdentifier {
+        let ident = value_enum.ident();
+        self.value_enums.push(value_enum);
+        ident
+    }
+
     pub fn add_enum(
         &mut self,
         name: impl Into<String>,
@@ -32,19 +79,54 @@ impl DbManager {
         members: Vec<&DbClassIdentifier>,
     ) {
         let name: String = name.into();
-        let base_class = self
-            .classes
-            .iter_mut()
-            .find(|m| m.ident.hash == base.hash)
-            .unwrap();
+        let Some(base_class) = self.classes.iter_mut().find(|m| m.ident.hash == base.hash) else {
+            self.errors.push(DbSchemaError::new(
+                Some(&name),
+                None,
+                DbSchemaErrorKind::MissingClass(base.name.clone()),
+            ));
+            return;
+        };
         base_class.extends_self(DbEnum::base_name(name.clone()));
         let mut enum_ = DbEnum::new(name, base_class.clone());
         for m in members {
-            enum_.add_type(&m);
+            enum_.add_type(m);
+            if self
+                .classes
+                .iter()
+                .any(|c| c.ident.hash == m.hash && c.soft_delete)
+            {
+                enum_.soft_deleted.push(m.hash.clone());
+            }
         }
 
         self.enums.push(enum_);
     }
+    pub fn add_enum_with_doc(
+        &mut self,
+        name: impl Into<String>,
+        doc: &str,
+        base: &DbClassIdentifier,
+        members: Vec<&DbClassIdentifier>,
+    ) {
+        let name: String = name.into();
+        self.add_enum(name.clone(), base, members);
+        if let Some(enum_) = self.enums.iter_mut().find(|e| e.name == name) {
+            enum_.doc = Some(doc.to_string());
+        }
+    }
+    pub fn add_enum_derive(&mut self, name: &str, derive: &str) {
+        if let Some(enum_) = self.enums.iter_mut().find(|e| e.name == name) {
+            enum_.derives.push((DbStruct::Enum, derive.to_string()));
+        }
+    }
+    pub fn add_enum_attribute(&mut self, name: &str, attribute: &str) {
+        if let Some(enum_) = self.enums.iter_mut().find(|e| e.name == name) {
+            enum_
+                .attributes
+                .push((DbStruct::Enum, attribute.to_string()));
+        }
+    }
     pub fn add_extension(
         &mut self,
         base: &DbClassIdentifier,
@@ -52,17 +134,27 @@ impl DbManager {
         ident: &DbClassIdentifier,
     ) {
         let name: String = name.into();
-        let base_class = self
+        let Some(base_class) = self
             .classes
             .iter()
             .find(|m| m.ident.hash == base.hash)
-            .unwrap()
-            .clone();
-        let class = self
-            .classes
-            .iter_mut()
-            .find(|m| m.ident.hash == ident.hash)
-            .unwrap();
+            .cloned()
+        else {
+            self.errors.push(DbSchemaError::new(
+                Some(&ident.name),
+                Some(&name),
+                DbSchemaErrorKind::MissingClass(base.name.clone()),
+            ));
+            return;
+        };
+        let Some(class) = self.classes.iter_mut().find(|m| m.ident.hash == ident.hash) else {
+            self.errors.push(DbSchemaError::new(
+                Some(&ident.name),
+                Some(&name),
+                DbSchemaErrorKind::MissingClass(ident.name.clone()),
+            ));
+            return;
+        };
         let fields = class.simple_fields();
         let simple = base_class
             .simple_fields()
@@ -72,100 +164,246 @@ impl DbManager {
             DbEnum::base_name(name.clone()),
             base_class,
             simple,
-        ))
+        ));
+        if let Some(enum_) = self.enums.iter_mut().find(|e| e.name == name) {
+            enum_.extended.push(ident.hash.clone());
+        }
     }
 }
 
 impl DbManager {
-    pub fn to_tokens(self) -> TokenStream {
-        let struct_tokens = self
-            .classes
-            .into_iter()
+    pub fn to_ddl(&self) -> String {
+        self.configured_classes()
+            .iter()
+            .flat_map(|c| c.to_ddl())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn to_tokens(self) -> Result<TokenStream, Vec<DbSchemaError>> {
+        self.validate()?;
+        let classes = self.configured_classes();
+        let ddl = classes.iter().map(ddl_tokens);
+        let shared = self.shared_tokens(quote! { &[#(#ddl),*] });
+        let class_tokens = classes.iter().map(class_tokens);
+        let enum_tokens = self.configured_enums().into_iter().map(|e| e.to_tokens());
+        Ok(quote! {
+            #shared
+            #(#class_tokens)*
+            #(#enum_tokens)*
+        })
+    }
+
+    pub fn to_modules(self) -> Result<Vec<(String, TokenStream)>, Vec<DbSchemaError>> {
+        self.validate()?;
+        self.validate_modules()?;
+        let classes = self.configured_classes();
+        let mut modules = classes
+            .iter()
             .map(|c| {
-                let struct_ = c.to_main_builder().to_tokens();
-                let id_struct = c.to_id_builder().to_tokens();
-                let create_struct = c.to_value_builder().to_tokens();
-                let serializer_struct = c.to_serializer_builder().to_tokens();
-                let impl_ = c.to_impl_tokens();
-                let impl_from = c.to_impl_from_tokens();
-                quote! {
-                    #id_struct
-                    #struct_
-                    #create_struct
-                    #serializer_struct
-                    #impl_
-                    #impl_from
-                }
+                let ddl = ddl_tokens(c);
+                let tokens = class_tokens(c);
+                let tokens = quote! {
+                    pub(super) const DB_DEFINITIONS: &[&str] = #ddl;
+
+                    #tokens
+                };
+                (to_snake_case(&c.ident.name), tokens)
             })
+            .chain(
+                self.configured_enums()
+                    .into_iter()
+                    .map(|e| (to_snake_case(&e.name), e.to_tokens())),
+            )
             .collect::<Vec<_>>();
-        let enum_tokens = self.enums.iter().map(|e| e.to_tokens()).collect::<Vec<_>>();
-        quote! {
-            use async_trait::async_trait;
-            use surrealdb::{Surreal, engine::remote::ws::Client};
-            use serde::{Deserialize, Serialize, Deserializer, Serializer, ser::Error};
-            use surrealdb::sql::Thing;
-            use futures::future::join_all;
-
-            #[derive(Debug, Deserialize)]
-            struct Record {
-                #[allow(dead_code)]
-                id: Thing,
-            }
+        modules.sort_by(|a, b| a.0.cmp(&b.0));
 
-            pub trait ClassHash{
-                fn class_hash() -> String;
+        // Every class module carries the DDL of its own class; `mod.rs` only lists them.
+        let class_modules = classes
+            .iter()
+            .map(|c| format_ident!("{}", to_snake_case(&c.ident.name)));
+        let shared = self.shared_tokens(quote! { &[#(#class_modules::DB_DEFINITIONS),*] });
+        let mod_decls = modules.iter().map(|(name, _)| {
+            let name = format_ident!("{}", name);
+            quote! {
+                mod #name;
+                pub use #name::*;
             }
+        });
+        let mut files = vec![(
+            "mod.rs".to_string(),
+            quote! {
+                #shared
+                #(#mod_decls)*
+            },
+        )];
+        files.extend(modules.into_iter().map(|(name, tokens)| {
+            (
+                format!("{}.rs", name),
+                quote! {
+                    use super::*;
+                    #[allow(unused_imports)]
+                    use super::{futures, serde, serde_json, surrealdb};
 
-            #[async_trait]
-            pub trait DbExtend<T> {
-                async fn db_extend(self, db: &Surreal<Client>) -> surrealdb::Result<T>;
-            }
+                    #tokens
+                },
+            )
+        }));
+        Ok(files)
+    }
 
-            #[async_trait]
-            impl<T: Send> DbExtend<T> for T {
-                async fn db_extend(self, _db: &Surreal<Client>) -> surrealdb::Result<T>{
-                    Ok(self)
+    fn configured_classes(&self) -> Vec<DbClass> {
+        self.classes
+            .iter()
+            .map(|c| {
+                let mut class = c.clone();
+                if self.timestamps {
+                    class = class.with_timestamps();
                 }
-            }
+                class.derives = self.derives.iter().chain(&c.derives).cloned().collect();
+                class.attributes = self
+                    .attributes
+                    .iter()
+                    .chain(&c.attributes)
+                    .cloned()
+                    .collect();
+                for f in class.fields.iter_mut() {
+                    if let DbClassField::LinkSingle(l) = f {
+                        l.boxed = self.links_back(&l.ident, &c.ident);
+                    }
+                }
+                class
+            })
+            .collect()
+    }
 
-            fn thing_to_string<'de, D>(deserializer: D) -> Result<String, D::Error>
-            where
-                D: Deserializer<'de>,
-            {
-                let original_value: Thing = Deserialize::deserialize(deserializer)?;
-                Ok(original_value.id.to_string())
+    fn configured_enums(&self) -> Vec<DbEnum> {
+        self.enums
+            .iter()
+            .map(|e| {
+                let mut enum_ = e.clone();
+                enum_.derives = self.derives_for(DbStruct::Enum, &e.derives);
+                enum_.attributes = self.attributes_for(DbStruct::Enum, &e.attributes);
+                enum_
+            })
+            .collect()
+    }
+
+    // Schema-wide derives for `target` come before the ones of the type itself.
+    fn derives_for(&self, target: DbStruct, own: &[(DbStruct, String)]) -> Vec<(DbStruct, String)> {
+        self.derives
+            .iter()
+            .filter(|(t, _)| *t == target)
+            .chain(own)
+            .cloned()
+            .collect()
+    }
+
+    fn attributes_for(
+        &self,
+        target: DbStruct,
+        own: &[(DbStruct, String)],
+    ) -> Vec<(DbStruct, String)> {
+        self.attributes
+            .iter()
+            .filter(|(t, _)| *t == target)
+            .chain(own)
+            .cloned()
+            .collect()
+    }
+
+    // Single links closing a cycle are boxed so the value structs have a finite size.
+    fn links_back(&self, from: &DbClassIdentifier, to: &DbClassIdentifier) -> bool {
+        let mut visited = vec![];
+        let mut pending = vec![from.clone()];
+        while let Some(ident) = pending.pop() {
+            if ident.hash == to.hash {
+                return true;
             }
-            fn db_link_to_thing<S, T, U>(db_link: &DbLink<T, U>, serializer: S) -> Result<S::Ok, S::Error>
-            where
-                S: Serializer,
-                T: Into<Thing>,
-                T: Clone
-            {
-                let DbLink::Existing(e) = db_link else {return Err(Error::custom("Unable to serialize DbLink::New"))};
-                let thing: Thing = e.clone().into();
-                thing.serialize(serializer)
+            if visited.contains(&ident.hash) {
+                continue;
             }
-            fn db_link_to_vec_thing<S, T, U>(db_link: &DbLink<Vec<T>, U>, serializer: S) -> Result<S::Ok, S::Error>
-            where
-                S: Serializer,
-                T: Into<Thing>,
-                T: Clone
-            {
-                let DbLink::Existing(e) = db_link else {return Err(Error::custom("Unable to serialize DbLink::New"))};
-                let vec: Vec<Thing> = e.iter().map(|i| i.clone().into()).collect();
-                vec.serialize(serializer)
+            visited.push(ident.hash.clone());
+            if let Some(class) = self.classes.iter().find(|c| c.ident.hash == ident.hash) {
+                pending.extend(class.link_single_fields().into_iter().map(|l| l.ident));
             }
+        }
+        false
+    }
 
-            #[derive(Debug, Serialize, Deserialize, Clone)]
-            pub enum DbLink<S, T> {
-                Existing(S),
-                New(T)
-            }
+    fn shared_tokens(&self, definitions: TokenStream) -> TokenStream {
+        let object_tokens = self.objects.iter().map(|o| {
+            let mut object = o.clone();
+            object.derives = self.derives_for(DbStruct::Object, &o.derives);
+            object.attributes = self.attributes_for(DbStruct::Object, &o.attributes);
+            object.to_tokens()
+        });
+        let value_enum_tokens = self.value_enums.iter().map(|e| {
+            let mut value_enum = e.clone();
+            value_enum.derives = self.derives_for(DbStruct::ValueEnum, &e.derives);
+            value_enum.attributes = self.attributes_for(DbStruct::ValueEnum, &e.attributes);
+            value_enum.to_tokens()
+        });
+        let uses = use_tokens();
+        quote! {
+            #uses
 
-            #(#struct_tokens)*
-            #(#enum_tokens)*
+            #[derive(Debug, Clone)]
+            pub struct WithDeleted<T>(pub T);
 
+            /// SurrealQL statements defining the schema, grouped by class.
+            pub const DB_DEFINITIONS: &[&[&str]] = #definitions;
 
+            pub async fn db_define(db: &Surreal<Any>) -> DbResult<()> {
+                db.query(DB_DEFINITIONS.concat().join("\n"))
+                    .await?
+                    .check()?;
+                Ok(())
+            }
+
+            #(#value_enum_tokens)*
+            #(#object_tokens)*
         }
     }
 }
+
+// Every dependency is imported from the runtime re-exports; the explicit imports take precedence
+// over crates of the same name the consumer may depend on directly.
+fn use_tokens() -> TokenStream {
+    quote! {
+        #[allow(unused_imports)]
+        use db_engine::runtime::v1::{futures, serde, serde_json, surrealdb};
+        use db_engine::runtime::v1::async_trait::async_trait;
+        use db_engine::runtime::v1::surrealdb::{Surreal, engine::any::Any};
+        use db_engine::runtime::v1::serde::{Deserialize, Serialize};
+        use db_engine::runtime::v1::surrealdb::sql::Thing;
+        use db_engine::runtime::v1::futures::future::join_all;
+        use db_engine::runtime::v1::futures::stream::{BoxStream, StreamExt, TryStreamExt};
+        use db_engine::runtime::v1::*;
+    }
+}
+
+// One string literal per statement, so the generated code keeps a statement per line.
+fn ddl_tokens(c: &DbClass) -> TokenStream {
+    let statements = c.to_ddl();
+    quote! { &[#(#statements),*] }
+}
+
+fn class_tokens(c: &DbClass) -> TokenStream {
+    let struct_ = c.to_main_builder().to_tokens();
+    let id_struct = c.to_id_builder().to_tokens();
+    let create_struct = c.to_value_builder().to_tokens();
+    let serializer_struct = c.to_serializer_builder().to_tokens();
+    let impl_ = c.to_impl_tokens();
+    let impl_from = c.to_impl_from_tokens();
+    let impl_repository = c.to_repository_tokens();
+    quote! {
+        #id_struct
+        #struct_
+        #create_struct
+        #serializer_struct
+        #impl_
+        #impl_from
+        #impl_repository
+    }
+}
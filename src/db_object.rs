@@ -0,0 +1,88 @@
+use proc_macro2::TokenStream;
+
+use crate::db_class::DbStruct;
+use crate::db_field::{
+    DbClassField, DbClassLinkMultiple, DbClassLinkSingle, DbClassSimpleField, DbClassVectorField,
+};
+use crate::db_validation::{DbSchemaError, DbSchemaErrorKind};
+use crate::syntax::struct_builder::{Field, StructSyntaxBuilder};
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+pub struct DbObjectIdentifier {
+    pub name: String,
+    pub(crate) fields: Vec<DbClassSimpleField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbObject {
+    pub(crate) name: String,
+    pub(crate) fields: Vec<DbClassSimpleField>,
+    doc: Option<String>,
+    pub(crate) derives: Vec<(DbStruct, String)>,
+    pub(crate) attributes: Vec<(DbStruct, String)>,
+    pub(crate) errors: Vec<DbSchemaError>,
+}
+
+impl DbObject {
+    pub fn with_name(name: &str) -> Self {
+        DbObject {
+            name: name.to_string(),
+            fields: vec![],
+            doc: None,
+            derives: vec![],
+            attributes: vec![],
+            errors: vec![],
+        }
+    }
+    pub fn with_doc(mut self, doc: &str) -> Self {
+        self.doc = Some(doc.to_string());
+        self
+    }
+    pub fn with_derive(mut self, derive: &str) -> Self {
+        self.derives.push((DbStruct::Object, derive.to_string()));
+        self
+    }
+    pub fn with_attribute(mut self, attribute: &str) -> Self {
+        self.attributes
+            .push((DbStruct::Object, attribute.to_string()));
+        self
+    }
+    pub fn add_field(mut self, field: DbClassField) -> Self {
+        match field {
+            DbClassField::Simple(f) => self.fields.push(f),
+            DbClassField::LinkSingle(DbClassLinkSingle { name, .. })
+            | DbClassField::LinkMultiple(DbClassLinkMultiple { name, .. })
+            | DbClassField::Vector(DbClassVectorField { name, .. }) => {
+                self.errors.push(DbSchemaError::new(
+                    Some(&self.name),
+                    Some(&name),
+                    DbSchemaErrorKind::UnsupportedObjectField,
+                ))
+            }
+        }
+        self
+    }
+    pub fn ident(&self) -> DbObjectIdentifier {
+        DbObjectIdentifier {
+            name: self.name.clone(),
+            fields: self.fields.clone(),
+        }
+    }
+    pub fn to_tokens(&self) -> TokenStream {
+        let mut builder = StructSyntaxBuilder::plain(&self.name);
+        builder.with_doc(self.doc.clone());
+        for (_, derive) in &self.derives {
+            builder.add_derive(derive);
+        }
+        for (_, attribute) in &self.attributes {
+            builder.add_attribute(attribute);
+        }
+        for f in &self.fields {
+            builder.add_field(
+                Field::with_decorators(&f.name, f.type_.rust_type(), f.type_.serde_decorators())
+                    .with_doc(f.doc.clone()),
+            );
+        }
+        builder.to_tokens()
+    }
+}
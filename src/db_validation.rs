@@ -0,0 +1,717 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+
+use syn::parse::Parser;
+
+use crate::db_class::{DbClassIdentifier, DbStruct};
+use crate::db_field::{DbClassField, DbClassSimpleField, DbFieldDefault, DbFieldRule, DbFieldType};
+use crate::db_manager::DbManager;
+use crate::syntax::{is_rust_keyword, to_snake_case};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DbSchemaErrorKind {
+    DuplicateName,
+    DuplicateField,
+    InvalidIdentifier(String),
+    ReservedKeyword(String),
+    ReservedField(String),
+    MissingClass(String),
+    MissingObject(String),
+    MissingValueEnum(String),
+    MixedValueEnum,
+    InvalidRustType(String),
+    InvalidRustExpression(String),
+    InvalidRegex(String),
+    InvalidRule(String),
+    InvalidDerive(String),
+    InvalidAttribute(String),
+    ReservedModuleName(String),
+    DuplicateModule(String),
+    NameCollision(String),
+    InvalidLiteral(String),
+    UnsupportedObjectField,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbSchemaError {
+    pub class: Option<String>,
+    pub field: Option<String>,
+    pub kind: DbSchemaErrorKind,
+}
+
+impl DbSchemaError {
+    pub fn new(class: Option<&str>, field: Option<&str>, kind: DbSchemaErrorKind) -> Self {
+        DbSchemaError {
+            class: class.map(|c| c.to_string()),
+            field: field.map(|f| f.to_string()),
+            kind,
+        }
+    }
+}
+
+impl Display for DbSchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.class, &self.field) {
+            (Some(class), Some(field)) => write!(f, "{}.{}: ", class, field)?,
+            (Some(class), None) => write!(f, "{}: ", class)?,
+            (None, Some(field)) => write!(f, "{}: ", field)?,
+            (None, None) => {}
+        }
+        match &self.kind {
+            DbSchemaErrorKind::DuplicateName => write!(f, "name is defined more than once"),
+            DbSchemaErrorKind::DuplicateField => write!(f, "field is defined more than once"),
+            DbSchemaErrorKind::InvalidIdentifier(name) => {
+                write!(f, "`{}` is not a valid Rust identifier", name)
+            }
+            DbSchemaErrorKind::ReservedKeyword(name) => {
+                write!(f, "`{}` is a reserved Rust keyword", name)
+            }
+            DbSchemaErrorKind::ReservedField(name) => {
+                write!(f, "`{}` is reserved for the record type tag", name)
+            }
+            DbSchemaErrorKind::MissingClass(name) => {
+                write!(f, "class `{}` was never added to the schema", name)
+            }
+            DbSchemaErrorKind::MissingObject(name) => {
+                write!(f, "object `{}` was never added to the schema", name)
+            }
+            DbSchemaErrorKind::MissingValueEnum(name) => {
+                write!(f, "value enum `{}` was never added to the schema", name)
+            }
+            DbSchemaErrorKind::MixedValueEnum => {
+                write!(f, "value enum mixes integer and string values")
+            }
+            DbSchemaErrorKind::InvalidRustType(type_) => {
+                write!(f, "`{}` is not a valid Rust type", type_)
+            }
+            DbSchemaErrorKind::InvalidRustExpression(expr) => {
+                write!(f, "`{}` is not a valid Rust expression", expr)
+            }
+            DbSchemaErrorKind::InvalidRegex(pattern) => {
+                write!(f, "`{}` is not a valid regular expression", pattern)
+            }
+            DbSchemaErrorKind::InvalidRule(rule) => {
+                write!(f, "the {} rule does not apply to this field type", rule)
+            }
+            DbSchemaErrorKind::InvalidDerive(derive) => {
+                write!(f, "`{}` is not a valid derive path", derive)
+            }
+            DbSchemaErrorKind::InvalidAttribute(attribute) => {
+                write!(f, "`{}` is not a valid outer attribute", attribute)
+            }
+            DbSchemaErrorKind::ReservedModuleName(name) => {
+                write!(f, "`{}` cannot be used as a module name", name)
+            }
+            DbSchemaErrorKind::DuplicateModule(name) => {
+                write!(f, "module `{}` is generated for more than one type", name)
+            }
+            DbSchemaErrorKind::NameCollision(name) => {
+                write!(f, "generated type `{}` collides with another type", name)
+            }
+            DbSchemaErrorKind::InvalidLiteral(literal) => {
+                write!(f, "`{}` is not a valid literal", literal)
+            }
+            DbSchemaErrorKind::UnsupportedObjectField => {
+                write!(f, "embedded objects only support simple fields")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DbSchemaError {}
+
+impl DbManager {
+    /// Checks the file names `to_modules` derives from class and enum names.
+    pub fn validate_modules(&self) -> Result<(), Vec<DbSchemaError>> {
+        let mut errors = vec![];
+        let names = self
+            .classes
+            .iter()
+            .map(|c| c.ident.name.clone())
+            .chain(self.enums.iter().map(|e| e.name.clone()));
+        let mut seen = HashSet::new();
+        for name in names {
+            let module = to_snake_case(&name);
+            if module == "mod" || is_rust_keyword(&module) {
+                errors.push(DbSchemaError::new(
+                    Some(&name),
+                    None,
+                    DbSchemaErrorKind::ReservedModuleName(module),
+                ));
+            } else if !seen.insert(module.clone()) {
+                errors.push(DbSchemaError::new(
+                    Some(&name),
+                    None,
+                    DbSchemaErrorKind::DuplicateModule(module),
+                ));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<DbSchemaError>> {
+        let mut errors = self.errors.clone();
+
+        let names = self
+            .classes
+            .iter()
+            .map(|c| c.ident.name.clone())
+            .chain(self.enums.iter().map(|e| e.name.clone()))
+            .chain(self.objects.iter().map(|o| o.name.clone()))
+            .chain(self.value_enums.iter().map(|e| e.ident().name));
+        let mut seen = HashSet::new();
+        for name in names {
+            check_ident(&mut errors, Some(&name), None, &name);
+            if !seen.insert(name.clone()) {
+                errors.push(DbSchemaError::new(
+                    Some(&name),
+                    None,
+                    DbSchemaErrorKind::DuplicateName,
+                ));
+            }
+        }
+
+        // Declared names are checked above; here only names derived from them can collide.
+        let derived = self
+            .classes
+            .iter()
+            .map(|c| (c.ident.name.clone(), c.generated_names()))
+            .chain(
+                self.enums
+                    .iter()
+                    .map(|e| (e.name.clone(), e.generated_names())),
+            );
+        let mut generated = seen.into_iter().collect::<HashSet<_>>();
+        generated.insert("WithDeleted".to_string());
+        for (owner, names) in derived {
+            for name in names.into_iter().filter(|n| *n != owner) {
+                if !generated.insert(name.clone()) {
+                    errors.push(DbSchemaError::new(
+                        Some(&owner),
+                        None,
+                        DbSchemaErrorKind::NameCollision(name),
+                    ));
+                }
+            }
+        }
+
+        for value_enum in &self.value_enums {
+            let ident = value_enum.ident();
+            let name = ident.name.clone();
+            if ident.is_mixed() {
+                errors.push(DbSchemaError::new(
+                    Some(&name),
+                    None,
+                    DbSchemaErrorKind::MixedValueEnum,
+                ));
+            }
+            for variant in value_enum.variant_names() {
+                check_ident(&mut errors, Some(&name), Some(variant), variant);
+            }
+        }
+
+        check_struct_config(&mut errors, None, &self.derives, &self.attributes);
+        for class in &self.classes {
+            check_struct_config(
+                &mut errors,
+                Some(&class.ident.name),
+                &class.derives,
+                &class.attributes,
+            );
+        }
+
+        for (name, derives, attributes) in self
+            .enums
+            .iter()
+            .map(|e| (&e.name, &e.derives, &e.attributes))
+            .chain(
+                self.objects
+                    .iter()
+                    .map(|o| (&o.name, &o.derives, &o.attributes)),
+            )
+            .chain(
+                self.value_enums
+                    .iter()
+                    .map(|e| (&e.name, &e.derives, &e.attributes)),
+            )
+        {
+            check_struct_config(&mut errors, Some(name), derives, attributes);
+        }
+
+        for enum_ in &self.enums {
+            for member in &enum_.idents {
+                self.check_class(&mut errors, &enum_.name, &member.name, member);
+            }
+        }
+
+        for object in &self.objects {
+            errors.extend(object.errors.iter().cloned());
+            self.check_fields(&mut errors, &object.name, &object.fields, false);
+        }
+
+        for class in &self.classes {
+            let class_name = &class.ident.name;
+            let simple = class.simple_fields();
+            self.check_fields(&mut errors, class_name, &simple, true);
+            let mut seen = simple
+                .iter()
+                .map(|f| f.name.clone())
+                .collect::<HashSet<_>>();
+            for field in &class.fields {
+                let (name, target) = match field {
+                    DbClassField::Simple(_) => continue,
+                    DbClassField::LinkSingle(l) => (&l.name, Some(&l.ident)),
+                    DbClassField::LinkMultiple(l) => (&l.name, Some(&l.ident)),
+                    DbClassField::Vector(v) => (&v.name, None),
+                };
+                check_field_ident(&mut errors, class_name, name);
+                if !seen.insert(to_snake_case(name)) {
+                    errors.push(DbSchemaError::new(
+                        Some(class_name),
+                        Some(name),
+                        DbSchemaErrorKind::DuplicateField,
+                    ));
+                }
+                if let Some(target) = target {
+                    self.check_class(&mut errors, class_name, name, target);
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn check_fields(
+        &self,
+        errors: &mut Vec<DbSchemaError>,
+        owner: &str,
+        fields: &[DbClassSimpleField],
+        has_id: bool,
+    ) {
+        let mut seen = HashSet::new();
+        if has_id {
+            seen.insert("id".to_string());
+        }
+        for f in fields {
+            let field = Some(f.name.as_str());
+            check_field_ident(errors, owner, &f.name);
+            if !seen.insert(to_snake_case(&f.name)) {
+                errors.push(DbSchemaError::new(
+                    Some(owner),
+                    field,
+                    DbSchemaErrorKind::DuplicateField,
+                ));
+            }
+            self.check_type(errors, owner, &f.name, &f.type_);
+            match &f.default {
+                Some(DbFieldDefault::Rust(expr)) if syn::parse_str::<syn::Expr>(expr).is_err() => {
+                    errors.push(DbSchemaError::new(
+                        Some(owner),
+                        field,
+                        DbSchemaErrorKind::InvalidRustExpression(expr.clone()),
+                    ));
+                }
+                Some(DbFieldDefault::Literal(literal))
+                    if f.type_.literal_tokens(literal).is_none() =>
+                {
+                    errors.push(DbSchemaError::new(
+                        Some(owner),
+                        field,
+                        DbSchemaErrorKind::InvalidLiteral(literal.clone()),
+                    ));
+                }
+                _ => {}
+            }
+            for rule in &f.rules {
+                let applies = match rule {
+                    DbFieldRule::Min(_) | DbFieldRule::Max(_) => f.type_.is_numeric(),
+                    DbFieldRule::MinLength(_)
+                    | DbFieldRule::MaxLength(_)
+                    | DbFieldRule::Regex(_) => f.type_ == DbFieldType::String,
+                    DbFieldRule::OneOf(_) => {
+                        f.type_.is_numeric()
+                            || matches!(
+                                f.type_,
+                                DbFieldType::String | DbFieldType::Char | DbFieldType::Bool
+                            )
+                    }
+                    DbFieldRule::Custom(_) => true,
+                };
+                if !applies {
+                    errors.push(DbSchemaError::new(
+                        Some(owner),
+                        field,
+                        DbSchemaErrorKind::InvalidRule(rule.name().to_string()),
+                    ));
+                    continue;
+                }
+                let literals = match rule {
+                    DbFieldRule::Min(v) | DbFieldRule::Max(v) => vec![v],
+                    DbFieldRule::OneOf(literals) => literals.iter().collect(),
+                    DbFieldRule::Regex(pattern) if regex::Regex::new(pattern).is_err() => {
+                        errors.push(DbSchemaError::new(
+                            Some(owner),
+                            field,
+                            DbSchemaErrorKind::InvalidRegex(pattern.clone()),
+                        ));
+                        continue;
+                    }
+                    DbFieldRule::Custom(path) if syn::parse_str::<syn::Path>(path).is_err() => {
+                        errors.push(DbSchemaError::new(
+                            Some(owner),
+                            field,
+                            DbSchemaErrorKind::InvalidRustExpression(path.clone()),
+                        ));
+                        continue;
+                    }
+                    _ => continue,
+                };
+                for literal in literals {
+                    if f.type_.literal_tokens(literal).is_none() {
+                        errors.push(DbSchemaError::new(
+                            Some(owner),
+                            field,
+                            DbSchemaErrorKind::InvalidLiteral(literal.clone()),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_type(
+        &self,
+        errors: &mut Vec<DbSchemaError>,
+        owner: &str,
+        field: &str,
+        type_: &DbFieldType,
+    ) {
+        let kind = match type_ {
+            DbFieldType::Array(t) | DbFieldType::Map(t) | DbFieldType::Option(t) => {
+                return self.check_type(errors, owner, field, t);
+            }
+            DbFieldType::Embedded(ident) if !self.objects.iter().any(|o| o.name == ident.name) => {
+                DbSchemaErrorKind::MissingObject(ident.name.clone())
+            }
+            DbFieldType::ValueEnum(ident)
+                if !self.value_enums.iter().any(|e| e.ident() == *ident) =>
+            {
+                DbSchemaErrorKind::MissingValueEnum(ident.name.clone())
+            }
+            DbFieldType::Custom { rust, .. } if syn::parse_str::<syn::Type>(rust).is_err() => {
+                DbSchemaErrorKind::InvalidRustType(rust.clone())
+            }
+            _ => return,
+        };
+        errors.push(DbSchemaError::new(Some(owner), Some(field), kind));
+    }
+
+    fn check_class(
+        &self,
+        errors: &mut Vec<DbSchemaError>,
+        owner: &str,
+        field: &str,
+        target: &DbClassIdentifier,
+    ) {
+        if !self.classes.iter().any(|c| c.ident.hash == target.hash) {
+            errors.push(DbSchemaError::new(
+                Some(owner),
+                Some(field),
+                DbSchemaErrorKind::MissingClass(target.name.clone()),
+            ));
+        }
+    }
+}
+
+fn check_ident(
+    errors: &mut Vec<DbSchemaError>,
+    class: Option<&str>,
+    field: Option<&str>,
+    name: &str,
+) {
+    if is_rust_keyword(name) {
+        errors.push(DbSchemaError::new(
+            class,
+            field,
+            DbSchemaErrorKind::ReservedKeyword(name.to_string()),
+        ));
+    } else if syn::parse_str::<syn::Ident>(name).is_err() {
+        errors.push(DbSchemaError::new(
+            class,
+            field,
+            DbSchemaErrorKind::InvalidIdentifier(name.to_string()),
+        ));
+    }
+}
+
+fn check_field_ident(errors: &mut Vec<DbSchemaError>, owner: &str, name: &str) {
+    let rust_name = to_snake_case(name);
+    if name == "type" {
+        errors.push(DbSchemaError::new(
+            Some(owner),
+            Some(name),
+            DbSchemaErrorKind::ReservedField(name.to_string()),
+        ));
+    } else if matches!(rust_name.as_str(), "self" | "Self" | "super" | "crate") {
+        errors.push(DbSchemaError::new(
+            Some(owner),
+            Some(name),
+            DbSchemaErrorKind::ReservedKeyword(rust_name),
+        ));
+    } else if !is_rust_keyword(&rust_name) && syn::parse_str::<syn::Ident>(&rust_name).is_err() {
+        errors.push(DbSchemaError::new(
+            Some(owner),
+            Some(name),
+            DbSchemaErrorKind::InvalidIdentifier(rust_name),
+        ));
+    }
+}
+
+fn check_struct_config(
+    errors: &mut Vec<DbSchemaError>,
+    class: Option<&str>,
+    derives: &[(DbStruct, String)],
+    attributes: &[(DbStruct, String)],
+) {
+    for (_, derive) in derives {
+        if syn::parse_str::<syn::Path>(derive).is_err() {
+            errors.push(DbSchemaError::new(
+                class,
+                None,
+                DbSchemaErrorKind::InvalidDerive(derive.clone()),
+            ));
+        }
+    }
+    for (_, attribute) in attributes {
+        if syn::Attribute::parse_outer.parse_str(attribute).is_err() {
+            errors.push(DbSchemaError::new(
+                class,
+                None,
+                DbSchemaErrorKind::InvalidAttribute(attribute.clone()),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db_class::DbClass;
+    use crate::db_field::{DbClassLinkSingle, DbClassSimpleField as SF};
+    use crate::db_object::DbObject;
+    use crate::db_value_enum::DbValueEnum;
+
+    fn kinds(mng: &DbManager) -> Vec<DbSchemaErrorKind> {
+        let errors = mng.validate().unwrap_err();
+        errors.into_iter().map(|e| e.kind).collect()
+    }
+
+    #[test]
+    fn names_are_checked() {
+        let mut mng = DbManager::new();
+        mng.add_class(DbClass::with_name("Bad Name"));
+        mng.add_class(DbClass::with_name("Self"));
+        mng.add_class(
+            DbClass::with_name("Person")
+                .add_field(SF::new("type", DbFieldType::String))
+                .add_field(SF::new("self", DbFieldType::String))
+                .add_field(SF::new("9lives", DbFieldType::I32))
+                .add_field(SF::new("name", DbFieldType::String))
+                .add_field(SF::new("name", DbFieldType::String)),
+        );
+        mng.add_object(DbObject::with_name("Person"));
+        assert_eq!(
+            kinds(&mng),
+            [
+                DbSchemaErrorKind::InvalidIdentifier("Bad Name".to_string()),
+                DbSchemaErrorKind::ReservedKeyword("Self".to_string()),
+                DbSchemaErrorKind::DuplicateName,
+                DbSchemaErrorKind::ReservedField("type".to_string()),
+                DbSchemaErrorKind::ReservedKeyword("self".to_string()),
+                DbSchemaErrorKind::InvalidIdentifier("9lives".to_string()),
+                DbSchemaErrorKind::DuplicateField,
+            ]
+        );
+    }
+
+    #[test]
+    fn types_are_checked() {
+        let mut mng = DbManager::new();
+        mng.add_value_enum(
+            DbValueEnum::with_name("Mixed")
+                .add_variant("Low")
+                .add_variant_with_int("High", 10),
+        );
+        let missing_object = DbObject::with_name("Address").ident();
+        let missing_enum = DbValueEnum::with_name("Status").add_variant("Open").ident();
+        let missing_class = DbClassIdentifier::new("Owner".to_string());
+        mng.add_class(
+            DbClass::with_name("Person")
+                .add_field(SF::new("address", DbFieldType::embedded(&missing_object)))
+                .add_field(SF::new("status", DbFieldType::value_enum(&missing_enum)))
+                .add_field(SF::new("raw", DbFieldType::custom("Vec<")))
+                .add_field(DbClassLinkSingle::new("owner", &missing_class)),
+        );
+        assert_eq!(
+            kinds(&mng),
+            [
+                DbSchemaErrorKind::MixedValueEnum,
+                DbSchemaErrorKind::MissingObject("Address".to_string()),
+                DbSchemaErrorKind::MissingValueEnum("Status".to_string()),
+                DbSchemaErrorKind::InvalidRustType("Vec<".to_string()),
+                DbSchemaErrorKind::MissingClass("Owner".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rules_and_defaults_are_checked() {
+        let mut mng = DbManager::new();
+        mng.add_class(
+            DbClass::with_name("Person")
+                .add_field(SF::with_rules(
+                    "age",
+                    DbFieldType::I32,
+                    vec![DbFieldRule::MinLength(1), DbFieldRule::Min("0".to_string())],
+                ))
+                .add_field(SF::with_rules(
+                    "code",
+                    DbFieldType::String,
+                    vec![DbFieldRule::Regex("(".to_string())],
+                ))
+                .add_field(SF::with_default(
+                    "score",
+                    DbFieldType::I32,
+                    DbFieldDefault::Rust("1 +".to_string()),
+                )),
+        );
+        assert_eq!(
+            kinds(&mng),
+            [
+                DbSchemaErrorKind::InvalidRule("min_length".to_string()),
+                DbSchemaErrorKind::InvalidRegex("(".to_string()),
+                DbSchemaErrorKind::InvalidRustExpression("1 +".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rules_must_fit_the_field_type() {
+        let mut mng = DbManager::new();
+        mng.add_class(
+            DbClass::with_name("Person")
+                .add_field(SF::with_rules(
+                    "age",
+                    DbFieldType::option(DbFieldType::I32),
+                    vec![DbFieldRule::Min("0".to_string())],
+                ))
+                .add_field(SF::with_rules(
+                    "name",
+                    DbFieldType::String,
+                    vec![
+                        DbFieldRule::Max("10".to_string()),
+                        DbFieldRule::OneOf(vec!["1".to_string()]),
+                    ],
+                ))
+                .add_field(SF::with_rules(
+                    "active",
+                    DbFieldType::Bool,
+                    vec![DbFieldRule::Min("0".to_string())],
+                ))
+                .add_field(SF::with_rules(
+                    "level",
+                    DbFieldType::U8,
+                    vec![
+                        DbFieldRule::Min("-1".to_string()),
+                        DbFieldRule::Max("300".to_string()),
+                        DbFieldRule::OneOf(vec!["1".to_string(), "0.5".to_string()]),
+                    ],
+                ))
+                .add_field(SF::with_rules(
+                    "score",
+                    DbFieldType::F64,
+                    vec![DbFieldRule::Max("\"high\"".to_string())],
+                ))
+                .add_field(SF::with_rules(
+                    "check",
+                    DbFieldType::String,
+                    vec![DbFieldRule::Custom("|v| v".to_string())],
+                )),
+        );
+        assert_eq!(
+            kinds(&mng),
+            [
+                DbSchemaErrorKind::InvalidRule("min".to_string()),
+                DbSchemaErrorKind::InvalidRule("max".to_string()),
+                DbSchemaErrorKind::InvalidLiteral("1".to_string()),
+                DbSchemaErrorKind::InvalidRule("min".to_string()),
+                DbSchemaErrorKind::InvalidLiteral("-1".to_string()),
+                DbSchemaErrorKind::InvalidLiteral("300".to_string()),
+                DbSchemaErrorKind::InvalidLiteral("0.5".to_string()),
+                DbSchemaErrorKind::InvalidLiteral("\"high\"".to_string()),
+                DbSchemaErrorKind::InvalidRustExpression("|v| v".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn literal_defaults_must_fit_the_field_type() {
+        let default = |name: &str, type_: DbFieldType, literal: &str| {
+            SF::with_default(name, type_, DbFieldDefault::Literal(literal.to_string()))
+        };
+        let mut mng = DbManager::new();
+        mng.add_class(
+            DbClass::with_name("Person")
+                .add_field(default("name", DbFieldType::String, "\"ann\""))
+                .add_field(default("score", DbFieldType::F64, "0"))
+                .add_field(default("level", DbFieldType::U8, "-1"))
+                .add_field(default("age", DbFieldType::I32, "0.5"))
+                .add_field(default(
+                    "nick",
+                    DbFieldType::option(DbFieldType::String),
+                    "\"a\"",
+                ))
+                .add_field(default("active", DbFieldType::Bool, "1")),
+        );
+        assert_eq!(
+            kinds(&mng),
+            [
+                DbSchemaErrorKind::InvalidLiteral("-1".to_string()),
+                DbSchemaErrorKind::InvalidLiteral("0.5".to_string()),
+                DbSchemaErrorKind::InvalidLiteral("\"a\"".to_string()),
+                DbSchemaErrorKind::InvalidLiteral("1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn derives_and_attributes_are_checked() {
+        let mut mng = DbManager::new();
+        mng.add_class(
+            DbClass::with_name("Person")
+                .with_derive(DbStruct::Main, "Partial Eq")
+                .with_attribute(DbStruct::Main, "derive"),
+        );
+        mng.add_value_enum(
+            DbValueEnum::with_name("Status")
+                .add_variant("Open")
+                .with_derive("Ord,"),
+        );
+        assert_eq!(
+            kinds(&mng),
+            [
+                DbSchemaErrorKind::InvalidDerive("Partial Eq".to_string()),
+                DbSchemaErrorKind::InvalidAttribute("derive".to_string()),
+                DbSchemaErrorKind::InvalidDerive("Ord,".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::db_class::DbStruct;
+use crate::syntax::{
+    ddl_builder::surql_string, doc_tokens, serde_crate_tokens, struct_config_tokens,
+};
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+pub enum DbValueEnumValue {
+    String(String),
+    Int(i64),
+}
+
+impl DbValueEnumValue {
+    fn surql(&self) -> String {
+        match self {
+            DbValueEnumValue::String(v) => surql_string(v),
+            DbValueEnumValue::Int(v) => v.to_string(),
+        }
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+pub struct DbValueEnumIdentifier {
+    pub name: String,
+    pub(crate) values: Vec<DbValueEnumValue>,
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+pub struct DbValueEnum {
+    pub(crate) name: String,
+    variants: Vec<(String, DbValueEnumValue)>,
+    doc: Option<String>,
+    pub(crate) derives: Vec<(DbStruct, String)>,
+    pub(crate) attributes: Vec<(DbStruct, String)>,
+}
+
+impl DbValueEnum {
+    pub fn with_name(name: &str) -> Self {
+        DbValueEnum {
+            name: name.to_string(),
+            variants: vec![],
+            doc: None,
+            derives: vec![],
+            attributes: vec![],
+        }
+    }
+    pub fn with_doc(mut self, doc: &str) -> Self {
+        self.doc = Some(doc.to_string());
+        self
+    }
+    pub fn with_derive(mut self, derive: &str) -> Self {
+        self.derives.push((DbStruct::ValueEnum, derive.to_string()));
+        self
+    }
+    pub fn with_attribute(mut self, attribute: &str) -> Self {
+        self.attributes
+            .push((DbStruct::ValueEnum, attribute.to_string()));
+        self
+    }
+    pub fn add_variant(self, name: &str) -> Self {
+        self.add_variant_with_value(name, name)
+    }
+    pub fn add_variant_with_value(mut self, name: &str, value: &str) -> Self {
+        self.variants.push((
+            name.to_string(),
+            DbValueEnumValue::String(value.to_string()),
+        ));
+        self
+    }
+    /// Adds a variant stored as an integer. An enum must not mix integer and string values.
+    pub fn add_variant_with_int(mut self, name: &str, value: i64) -> Self {
+        self.variants
+            .push((name.to_string(), DbValueEnumValue::Int(value)));
+        self
+    }
+    pub fn variant_names(&self) -> impl Iterator<Item = &String> {
+        self.variants.iter().map(|(name, _)| name)
+    }
+    pub fn ident(&self) -> DbValueEnumIdentifier {
+        DbValueEnumIdentifier {
+            name: self.name.clone(),
+            values: self.variants.iter().map(|(_, v)| v.clone()).collect(),
+        }
+    }
+    pub fn to_tokens(&self) -> TokenStream {
+        let serde_crate = serde_crate_tokens();
+        let name = format_ident!("{}", self.name);
+        let doc = doc_tokens(&self.doc);
+        let (derives, attributes) = struct_config_tokens(
+            &self.derives,
+            &self.attributes,
+            DbStruct::ValueEnum,
+            &[
+                "Debug",
+                "Serialize",
+                "Deserialize",
+                "Clone",
+                "Copy",
+                "PartialEq",
+                "Eq",
+                "Hash",
+            ],
+        );
+        if !self.ident().is_int() {
+            let variants = self.variants.iter().map(|(variant, value)| {
+                let variant = format_ident!("{}", variant);
+                let DbValueEnumValue::String(value) = value else {
+                    unreachable!()
+                };
+                quote! {
+                    #[serde(rename = #value)]
+                    #variant
+                }
+            });
+            return quote! {
+                #doc
+                #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash #(, #derives)*)]
+                #serde_crate
+                #(#attributes)*
+                pub enum #name {
+                    #(#variants,)*
+                }
+            };
+        }
+        let (variants, values): (Vec<_>, Vec<_>) = self
+            .variants
+            .iter()
+            .map(|(variant, value)| {
+                let DbValueEnumValue::Int(value) = value else {
+                    unreachable!()
+                };
+                (format_ident!("{}", variant), *value)
+            })
+            .unzip();
+        let error = format!("unknown {} value {{}}", self.name);
+        quote! {
+            #doc
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash #(, #derives)*)]
+            #(#attributes)*
+            pub enum #name {
+                #(#variants,)*
+            }
+
+            impl Serialize for #name {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    let value: i64 = match self {
+                        #(#name::#variants => #values,)*
+                    };
+                    serializer.serialize_i64(value)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for #name {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    match i64::deserialize(deserializer)? {
+                        #(#values => Ok(#name::#variants),)*
+                        value => Err(<D::Error as serde::de::Error>::custom(format!(#error, value))),
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl DbValueEnumIdentifier {
+    pub fn surql_values(&self) -> String {
+        let values = self.values.iter().map(|v| v.surql()).collect::<Vec<_>>();
+        format!("[{}]", values.join(", "))
+    }
+    pub(crate) fn is_int(&self) -> bool {
+        self.values
+            .first()
+            .is_some_and(|v| matches!(v, DbValueEnumValue::Int(_)))
+    }
+    pub(crate) fn is_mixed(&self) -> bool {
+        self.values
+            .iter()
+            .any(|v| matches!(v, DbValueEnumValue::Int(_)))
+            && self
+                .values
+                .iter()
+                .any(|v| matches!(v, DbValueEnumValue::String(_)))
+    }
+}
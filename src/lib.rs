@@ -1,5 +1,11 @@
+pub mod build;
 pub mod db_class;
 pub mod db_enum;
 pub mod db_field;
 pub mod db_manager;
+pub mod db_object;
+pub mod db_validation;
+pub mod db_value_enum;
+#[cfg(feature = "runtime")]
+pub mod runtime;
 pub mod syntax;
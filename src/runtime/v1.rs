@@ -0,0 +1,572 @@
+use ::async_trait::async_trait;
+use ::serde::{de::DeserializeOwned, ser::Error, Deserialize, Deserializer, Serialize, Serializer};
+use ::surrealdb::{engine::any::Any, sql::Thing, Surreal};
+
+mod memory;
+#[doc(hidden)]
+pub mod wide_int;
+
+pub use memory::DbMemory;
+
+// Generated code reaches its dependencies through these re-exports, so consumers only need
+// `db_engine` itself.
+pub use ::async_trait;
+pub use ::futures;
+pub use ::regex;
+pub use ::serde;
+pub use ::serde_json;
+pub use ::surrealdb;
+
+pub trait ClassHash {
+    fn class_hash() -> String;
+
+    /// Fields the database fills from `DEFAULT` or `VALUE` clauses.
+    fn computed_fields() -> &'static [DbComputed] {
+        &[]
+    }
+}
+
+/// A field computed by the database, passed along to backends that do not run the DDL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DbComputed {
+    /// Set to the current time when the record is created.
+    CreatedAt(&'static str),
+    /// Set to the current time on every write.
+    Now(&'static str),
+    /// Counts the writes of the record, starting at 1.
+    Version(&'static str),
+    /// Any other SurrealQL expression, which only SurrealDB can evaluate.
+    Surql(&'static str),
+}
+
+#[derive(Debug, Clone)]
+pub struct DbValidationError {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum DbError {
+    Surreal(surrealdb::Error),
+    Validation(Vec<DbValidationError>),
+    Conflict(String),
+    InvalidCursor(String),
+    Json(serde_json::Error),
+    Unsupported(String),
+}
+
+pub type DbResult<T> = Result<T, DbError>;
+
+impl From<surrealdb::Error> for DbError {
+    fn from(value: surrealdb::Error) -> Self {
+        DbError::Surreal(value)
+    }
+}
+
+impl From<serde_json::Error> for DbError {
+    fn from(value: serde_json::Error) -> Self {
+        DbError::Json(value)
+    }
+}
+
+impl From<Vec<DbValidationError>> for DbError {
+    fn from(value: Vec<DbValidationError>) -> Self {
+        DbError::Validation(value)
+    }
+}
+
+impl std::fmt::Display for DbError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DbError::Surreal(e) => write!(f, "{}", e),
+            DbError::Validation(errors) => {
+                let errors = errors
+                    .iter()
+                    .map(|e| format!("{} {}", e.field, e.message))
+                    .collect::<Vec<_>>();
+                write!(f, "Validation failed: {}", errors.join(", "))
+            }
+            DbError::Conflict(id) => {
+                write!(f, "Record {} was modified concurrently", id)
+            }
+            DbError::InvalidCursor(e) => write!(f, "Invalid cursor: {}", e),
+            DbError::Json(e) => write!(f, "{}", e),
+            DbError::Unsupported(e) => write!(f, "Unsupported: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DbGuard {
+    pub active: bool,
+    pub version: Option<u64>,
+}
+
+impl DbGuard {
+    fn conditions(&self) -> Vec<&'static str> {
+        let mut conditions = vec![];
+        if self.active {
+            conditions.push("deleted_at IS NONE");
+        }
+        if self.version.is_some() {
+            conditions.push("version = $version");
+        }
+        conditions
+    }
+}
+
+/// Id and version of a record written by [`DbBackend::update`] or [`DbBackend::merge`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct DbUpdated {
+    #[serde(deserialize_with = "thing_to_string")]
+    pub id: String,
+    #[serde(default)]
+    pub version: Option<u64>,
+}
+
+/// Record storage used by the generated create, get, update, merge, delete and restore
+/// methods. `computed` lists the fields SurrealDB fills from the table definition, so
+/// backends that do not run the DDL can compute them.
+///
+/// Lists, streams, aggregates, history and vector search run SurrealQL queries and are
+/// not part of this trait; they take a `Surreal<Any>` connection.
+#[async_trait]
+pub trait DbBackend: Send + Sync {
+    async fn create<T: Serialize + Send + 'static>(
+        &self,
+        table: String,
+        computed: &[DbComputed],
+        content: T,
+    ) -> DbResult<String>;
+    async fn select<T: DeserializeOwned + Send>(
+        &self,
+        table: String,
+        id: &str,
+    ) -> DbResult<Option<T>>;
+    async fn update<T: Serialize + Send + 'static>(
+        &self,
+        table: String,
+        id: &str,
+        computed: &[DbComputed],
+        content: T,
+        guard: DbGuard,
+    ) -> DbResult<Option<DbUpdated>>;
+    async fn merge<T: Serialize + Send + 'static>(
+        &self,
+        table: String,
+        id: &str,
+        computed: &[DbComputed],
+        patch: T,
+        guard: DbGuard,
+    ) -> DbResult<Option<DbUpdated>>;
+    async fn delete(&self, table: String, id: &str) -> DbResult<Option<String>>;
+    async fn soft_delete(
+        &self,
+        table: String,
+        id: &str,
+        computed: &[DbComputed],
+    ) -> DbResult<Option<String>>;
+    async fn restore(
+        &self,
+        table: String,
+        id: &str,
+        computed: &[DbComputed],
+    ) -> DbResult<Option<String>>;
+}
+
+#[derive(Deserialize)]
+struct DbKey {
+    #[serde(deserialize_with = "thing_to_string")]
+    id: String,
+}
+
+#[async_trait]
+impl DbBackend for Surreal<Any> {
+    async fn create<T: Serialize + Send + 'static>(
+        &self,
+        table: String,
+        _computed: &[DbComputed],
+        content: T,
+    ) -> DbResult<String> {
+        let created: Vec<DbKey> = Surreal::create(self, table).content(content).await?;
+        Ok(created.into_iter().next().unwrap().id)
+    }
+
+    async fn select<T: DeserializeOwned + Send>(
+        &self,
+        table: String,
+        id: &str,
+    ) -> DbResult<Option<T>> {
+        Ok(Surreal::select(self, (table, id)).await?)
+    }
+
+    async fn update<T: Serialize + Send + 'static>(
+        &self,
+        table: String,
+        id: &str,
+        _computed: &[DbComputed],
+        content: T,
+        guard: DbGuard,
+    ) -> DbResult<Option<DbUpdated>> {
+        if guard.conditions().is_empty() {
+            return Ok(Surreal::update(self, (table, id)).content(content).await?);
+        }
+        guarded_update(self, "CONTENT", table, id, content, guard).await
+    }
+
+    async fn merge<T: Serialize + Send + 'static>(
+        &self,
+        table: String,
+        id: &str,
+        _computed: &[DbComputed],
+        patch: T,
+        guard: DbGuard,
+    ) -> DbResult<Option<DbUpdated>> {
+        if guard.conditions().is_empty() {
+            return Ok(Surreal::update(self, (table, id)).merge(patch).await?);
+        }
+        guarded_update(self, "MERGE", table, id, patch, guard).await
+    }
+
+    async fn delete(&self, table: String, id: &str) -> DbResult<Option<String>> {
+        let deleted: Option<DbKey> = Surreal::delete(self, (table, id)).await?;
+        Ok(deleted.map(|k| k.id))
+    }
+
+    async fn soft_delete(
+        &self,
+        table: String,
+        id: &str,
+        _computed: &[DbComputed],
+    ) -> DbResult<Option<String>> {
+        let deleted: Option<DbKey> = self
+            .query("UPDATE type::thing($table, $id) SET deleted_at = time::now() WHERE deleted_at IS NONE")
+            .bind(("table", table))
+            .bind(("id", id))
+            .await?
+            .take(0)?;
+        Ok(deleted.map(|k| k.id))
+    }
+
+    async fn restore(
+        &self,
+        table: String,
+        id: &str,
+        _computed: &[DbComputed],
+    ) -> DbResult<Option<String>> {
+        let restored: Option<DbKey> = self
+            .query("UPDATE type::thing($table, $id) SET deleted_at = NONE WHERE deleted_at IS NOT NONE")
+            .bind(("table", table))
+            .bind(("id", id))
+            .await?
+            .take(0)?;
+        Ok(restored.map(|k| k.id))
+    }
+}
+
+async fn guarded_update<T: Serialize>(
+    db: &Surreal<Any>,
+    mode: &str,
+    table: String,
+    id: &str,
+    content: T,
+    guard: DbGuard,
+) -> DbResult<Option<DbUpdated>> {
+    Ok(db
+        .query(format!(
+            "UPDATE type::thing($table, $id) {} $content WHERE {}",
+            mode,
+            guard.conditions().join(" AND ")
+        ))
+        .bind(("table", table))
+        .bind(("id", id))
+        .bind(("content", content))
+        .bind(("version", guard.version))
+        .await?
+        .take(0)?)
+}
+
+#[async_trait]
+pub trait DbExtend<T> {
+    async fn db_extend<B: DbBackend>(self, db: &B) -> DbResult<T>;
+}
+
+#[async_trait]
+impl<T: Send> DbExtend<T> for T {
+    async fn db_extend<B: DbBackend>(self, _db: &B) -> DbResult<T> {
+        Ok(self)
+    }
+}
+
+pub trait Entity: Sized + Send + Sync {
+    type Id: ClassHash + Send + Sync;
+    type Value: Send;
+    type Serializer: Send;
+    type OrderBy: DbOrderBy + Default + Send;
+
+    fn id(&self) -> Self::Id;
+}
+
+#[async_trait]
+pub trait Repository: Entity {
+    async fn create<B: DbBackend>(db: &B, value: Self::Value) -> DbResult<Self::Id>;
+    async fn create_get<B: DbBackend>(db: &B, value: Self::Value) -> DbResult<Self>;
+    async fn get<B: DbBackend>(db: &B, id: &Self::Id) -> DbResult<Option<Self>>;
+    async fn update<B: DbBackend>(&mut self, db: &B) -> DbResult<Option<Self::Id>>;
+    async fn delete<B: DbBackend>(db: &B, id: &Self::Id) -> DbResult<Option<Self::Id>>;
+    async fn list(db: &Surreal<Any>, query: DbListQuery<Self::OrderBy>) -> DbResult<Page<Self>>;
+    async fn resolve<B: DbBackend>(
+        serializer: Self::Serializer,
+        id: String,
+        db: &B,
+    ) -> DbResult<Option<Self>>;
+}
+
+pub fn thing_to_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let original_value: Thing = Deserialize::deserialize(deserializer)?;
+    Ok(original_value.id.to_string())
+}
+pub fn db_link_to_thing<S, T, U>(db_link: &DbLink<T, U>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Into<Thing>,
+    T: Clone,
+{
+    let DbLink::Existing(e) = db_link else {
+        return Err(Error::custom("Unable to serialize DbLink::New"));
+    };
+    let thing: Thing = e.clone().into();
+    thing.serialize(serializer)
+}
+pub fn db_link_to_vec_thing<S, T, U>(
+    db_link: &DbLink<Vec<T>, U>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Into<Thing>,
+    T: Clone,
+{
+    let DbLink::Existing(e) = db_link else {
+        return Err(Error::custom("Unable to serialize DbLink::New"));
+    };
+    let vec: Vec<Thing> = e.iter().map(|i| i.clone().into()).collect();
+    vec.serialize(serializer)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum DbLink<S, T> {
+    Existing(S),
+    New(T),
+}
+
+#[doc(hidden)]
+#[derive(Deserialize)]
+pub struct DbRecord<T> {
+    #[serde(deserialize_with = "thing_to_string")]
+    pub id: String,
+    #[serde(flatten)]
+    pub value: T,
+}
+
+#[derive(Deserialize)]
+struct DbCount {
+    count: u64,
+}
+
+pub trait DbOrderBy {
+    fn field(&self) -> &'static str;
+    /// SurrealQL type the cursor value is cast to when comparing it with the field.
+    fn surql_type(&self) -> &'static str;
+}
+
+#[derive(Debug, Clone)]
+pub enum DbPagination {
+    Offset { offset: u64, limit: u64 },
+    Cursor { after: Option<String>, limit: u64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct DbListQuery<O> {
+    pub pagination: DbPagination,
+    pub order_by: O,
+    pub with_total: bool,
+    pub with_deleted: bool,
+}
+
+impl<O: Default> DbListQuery<O> {
+    pub fn offset(offset: u64, limit: u64) -> Self {
+        DbListQuery {
+            pagination: DbPagination::Offset { offset, limit },
+            order_by: O::default(),
+            with_total: false,
+            with_deleted: false,
+        }
+    }
+
+    pub fn cursor(after: Option<String>, limit: u64) -> Self {
+        DbListQuery {
+            pagination: DbPagination::Cursor { after, limit },
+            order_by: O::default(),
+            with_total: false,
+            with_deleted: false,
+        }
+    }
+}
+
+impl<O> DbListQuery<O> {
+    pub fn order_by(mut self, order_by: O) -> Self {
+        self.order_by = order_by;
+        self
+    }
+
+    pub fn with_total(mut self) -> Self {
+        self.with_total = true;
+        self
+    }
+
+    pub fn with_deleted(mut self) -> Self {
+        self.with_deleted = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub total: Option<u64>,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DbAggregate<K> {
+    pub key: Option<K>,
+    pub count: u64,
+    pub sum: Option<f64>,
+    pub avg: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl<K> DbAggregate<K> {
+    #[doc(hidden)]
+    pub fn map_key<T>(self, f: impl FnOnce(K) -> T) -> DbAggregate<T> {
+        DbAggregate {
+            key: self.key.map(f),
+            count: self.count,
+            sum: self.sum,
+            avg: self.avg,
+            min: self.min,
+            max: self.max,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub async fn db_list_records<O: DbOrderBy, R: DeserializeOwned>(
+    db: &Surreal<Any>,
+    tables: Vec<String>,
+    query: &DbListQuery<O>,
+    conditions: Vec<String>,
+) -> DbResult<(Vec<R>, bool, Option<u64>)> {
+    let order = query.order_by.field();
+    let from = (0..tables.len())
+        .map(|i| format!("type::table($table_{})", i))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let filter = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+    let (limit, start, cursor) = match &query.pagination {
+        DbPagination::Offset { offset, limit } => (*limit, *offset, None),
+        DbPagination::Cursor { after, limit } => {
+            let cursor = after
+                .as_ref()
+                .map(|c| serde_json::from_str::<(serde_json::Value, String, String)>(c))
+                .transpose()
+                .map_err(|e| DbError::InvalidCursor(e.to_string()))?;
+            (*limit, 0, cursor)
+        }
+    };
+    let mut page_conditions = conditions.clone();
+    if cursor.is_some() {
+        if order == "id" {
+            page_conditions.push("id > $cursor_id".to_string());
+        } else {
+            page_conditions.push(format!(
+                "({0} > <{1}> $cursor_value OR ({0} = <{1}> $cursor_value AND id > $cursor_id))",
+                order,
+                query.order_by.surql_type()
+            ));
+        }
+    }
+    let page_filter = if page_conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", page_conditions.join(" AND "))
+    };
+    let order_clause = if order == "id" {
+        "id".to_string()
+    } else {
+        format!("{}, id", order)
+    };
+    let mut statement = db
+        .query(format!(
+            "SELECT * FROM {}{} ORDER BY {} LIMIT $limit START $start",
+            from, page_filter, order_clause
+        ))
+        .bind(("limit", limit + 1))
+        .bind(("start", start));
+    if query.with_total {
+        statement = statement.query(format!("SELECT count() FROM {}{} GROUP ALL", from, filter));
+    }
+    for (i, table) in tables.into_iter().enumerate() {
+        statement = statement.bind((format!("table_{}", i), table));
+    }
+    if let Some((value, table, id)) = cursor {
+        statement = statement
+            .bind(("cursor_value", value))
+            .bind(("cursor_id", Thing::from((table, id))));
+    }
+    let mut response = statement.await?;
+    let mut records: Vec<R> = response.take(0)?;
+    let has_more = records.len() as u64 > limit;
+    records.truncate(limit as usize);
+    let total = if query.with_total {
+        let count: Option<DbCount> = response.take(1)?;
+        Some(count.map(|c| c.count).unwrap_or(0))
+    } else {
+        None
+    };
+    Ok((records, has_more, total))
+}
+
+/// Builds the cursor after the last record of a page, before its links are resolved, so
+/// records dropped while resolving do not move the cursor back.
+#[doc(hidden)]
+pub fn db_next_cursor<O: DbOrderBy, S: Serialize>(
+    query: &DbListQuery<O>,
+    has_more: bool,
+    table: String,
+    last: Option<&DbRecord<S>>,
+) -> Option<String> {
+    if !has_more || matches!(query.pagination, DbPagination::Offset { .. }) {
+        return None;
+    }
+    let last = last?;
+    let order = query.order_by.field();
+    let value = if order == "id" {
+        serde_json::Value::Null
+    } else {
+        serde_json::to_value(&last.value).ok()?.get(order)?.clone()
+    };
+    serde_json::to_string(&(value, table, &last.id)).ok()
+}
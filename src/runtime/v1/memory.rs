@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use surrealdb::sql::{Datetime, Id};
+
+use super::{DbBackend, DbComputed, DbError, DbGuard, DbResult, DbUpdated};
+
+/// In-memory [`DbBackend`] keeping one map of records per table hash.
+///
+/// Records are stored as JSON, so links and prefetching resolve exactly as
+/// they do against SurrealDB. Timestamps and version counters are filled on
+/// every write like the generated `VALUE` clauses do. Other SurrealQL
+/// `DEFAULT` and `VALUE` expressions cannot be evaluated, so writes to classes
+/// using them fail with [`DbError::Unsupported`]; `ASSERT` clauses are not
+/// checked. Updates keep stored fields missing from the new content.
+/// Records stored with [`DbMemory::insert`] are kept as given.
+#[derive(Debug, Default)]
+pub struct DbMemory {
+    tables: Mutex<HashMap<String, HashMap<String, Value>>>,
+}
+
+impl DbMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores a fixture record under the given id, replacing any existing one.
+    #[allow(clippy::result_large_err)]
+    pub fn insert(
+        &self,
+        table: impl Into<String>,
+        id: impl Into<String>,
+        content: impl Serialize,
+    ) -> DbResult<()> {
+        let content = serde_json::to_value(content)?;
+        self.tables
+            .lock()
+            .unwrap()
+            .entry(table.into())
+            .or_default()
+            .insert(id.into(), content);
+        Ok(())
+    }
+
+    pub fn record(&self, table: &str, id: &str) -> Option<Value> {
+        self.tables.lock().unwrap().get(table)?.get(id).cloned()
+    }
+
+    /// All records of a table, ordered by id.
+    pub fn records(&self, table: &str) -> Vec<(String, Value)> {
+        let tables = self.tables.lock().unwrap();
+        let mut records: Vec<_> = tables
+            .get(table)
+            .map(|t| t.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+        records.sort_by(|a, b| a.0.cmp(&b.0));
+        records
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn modify(
+        &self,
+        table: &str,
+        id: &str,
+        computed: &[DbComputed],
+        f: impl FnOnce(&mut Value) -> bool,
+    ) -> DbResult<Option<String>> {
+        check_computed(computed)?;
+        let mut tables = self.tables.lock().unwrap();
+        let Some(record) = tables.get_mut(table).and_then(|t| t.get_mut(id)) else {
+            return Ok(None);
+        };
+        let before = record.clone();
+        if !f(record) {
+            return Ok(None);
+        }
+        compute(record, Some(&before), computed)?;
+        Ok(Some(id.to_string()))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn write(
+        &self,
+        table: String,
+        id: &str,
+        computed: &[DbComputed],
+        content: Value,
+        guard: DbGuard,
+        apply: fn(&mut Value, Value),
+    ) -> DbResult<Option<DbUpdated>> {
+        check_computed(computed)?;
+        let mut tables = self.tables.lock().unwrap();
+        let table = tables.entry(table).or_default();
+        if (guard.active || guard.version.is_some()) && !table.contains_key(id) {
+            return Ok(None);
+        }
+        let record = table.entry(id.to_string()).or_insert(Value::Null);
+        let version = record.get("version").and_then(Value::as_u64);
+        if (guard.active && is_deleted(record))
+            || (guard.version.is_some() && version != guard.version)
+        {
+            return Ok(None);
+        }
+        let before = (!record.is_null()).then(|| record.clone());
+        apply(record, content);
+        compute(record, before.as_ref(), computed)?;
+        Ok(Some(DbUpdated {
+            id: id.to_string(),
+            version: record.get("version").and_then(Value::as_u64),
+        }))
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn check_computed(computed: &[DbComputed]) -> DbResult<()> {
+    match computed.iter().find_map(|c| match c {
+        DbComputed::Surql(field) => Some(field),
+        _ => None,
+    }) {
+        Some(field) => Err(DbError::Unsupported(format!(
+            "DbMemory cannot evaluate the SurrealQL expression of field `{}`",
+            field
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn compute(record: &mut Value, before: Option<&Value>, computed: &[DbComputed]) -> DbResult<()> {
+    let now = serde_json::to_value(Datetime::default())?;
+    let previous = |field: &str| before.and_then(|b| b.get(field)).filter(|v| !v.is_null());
+    for c in computed {
+        match *c {
+            DbComputed::CreatedAt(field) => {
+                record[field] = previous(field).cloned().unwrap_or_else(|| now.clone());
+            }
+            DbComputed::Now(field) => record[field] = now.clone(),
+            DbComputed::Version(field) => {
+                let version = previous(field).and_then(Value::as_u64).unwrap_or(0);
+                record[field] = (version + 1).into();
+            }
+            DbComputed::Surql(_) => unreachable!("checked before writing"),
+        }
+    }
+    Ok(())
+}
+
+fn replace(record: &mut Value, content: Value) {
+    if let (Value::Object(old), Value::Object(mut new)) = (&*record, content.clone()) {
+        for (key, value) in old {
+            new.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        *record = Value::Object(new);
+    } else {
+        *record = content;
+    }
+}
+
+fn merge(record: &mut Value, patch: Value) {
+    match (record, patch) {
+        (Value::Object(old), Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge(old.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (record, patch) => *record = patch,
+    }
+}
+
+fn is_deleted(record: &Value) -> bool {
+    !record.get("deleted_at").unwrap_or(&Value::Null).is_null()
+}
+
+#[async_trait]
+impl DbBackend for DbMemory {
+    async fn create<T: Serialize + Send + 'static>(
+        &self,
+        table: String,
+        computed: &[DbComputed],
+        content: T,
+    ) -> DbResult<String> {
+        check_computed(computed)?;
+        let mut content = serde_json::to_value(content)?;
+        compute(&mut content, None, computed)?;
+        let id = Id::rand().to_raw();
+        self.insert(table, id.clone(), content)?;
+        Ok(id)
+    }
+
+    async fn select<T: DeserializeOwned + Send>(
+        &self,
+        table: String,
+        id: &str,
+    ) -> DbResult<Option<T>> {
+        let Some(record) = self.record(&table, id) else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_value(record)?))
+    }
+
+    async fn update<T: Serialize + Send + 'static>(
+        &self,
+        table: String,
+        id: &str,
+        computed: &[DbComputed],
+        content: T,
+        guard: DbGuard,
+    ) -> DbResult<Option<DbUpdated>> {
+        let content = serde_json::to_value(content)?;
+        self.write(table, id, computed, content, guard, replace)
+    }
+
+    async fn merge<T: Serialize + Send + 'static>(
+        &self,
+        table: String,
+        id: &str,
+        computed: &[DbComputed],
+        patch: T,
+        guard: DbGuard,
+    ) -> DbResult<Option<DbUpdated>> {
+        let patch = serde_json::to_value(patch)?;
+        self.write(table, id, computed, patch, guard, merge)
+    }
+
+    async fn delete(&self, table: String, id: &str) -> DbResult<Option<String>> {
+        let mut tables = self.tables.lock().unwrap();
+        let deleted = tables.get_mut(&table).and_then(|t| t.remove(id));
+        Ok(deleted.map(|_| id.to_string()))
+    }
+
+    async fn soft_delete(
+        &self,
+        table: String,
+        id: &str,
+        computed: &[DbComputed],
+    ) -> DbResult<Option<String>> {
+        let now = serde_json::to_value(Datetime::default())?;
+        self.modify(&table, id, computed, |record| {
+            if is_deleted(record) {
+                return false;
+            }
+            record["deleted_at"] = now;
+            true
+        })
+    }
+
+    async fn restore(
+        &self,
+        table: String,
+        id: &str,
+        computed: &[DbComputed],
+    ) -> DbResult<Option<String>> {
+        self.modify(&table, id, computed, |record| {
+            if !is_deleted(record) {
+                return false;
+            }
+            record["deleted_at"] = Value::Null;
+            true
+        })
+    }
+}
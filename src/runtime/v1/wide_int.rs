@@ -0,0 +1,87 @@
+//! Serde adapter for `i128`/`u128` fields.
+//!
+//! SurrealDB stores them as 64-bit `int`s, and serde cannot buffer 128-bit integers while
+//! deserializing tagged or flattened structs, so they travel as `i64`/`u64`.
+
+use std::collections::HashMap;
+
+use serde::{ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+pub trait WideInt: Sized {
+    fn serialize_narrow<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+    fn deserialize_narrow<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+struct Narrow<'a, T>(&'a T);
+
+impl<T: WideInt> Serialize for Narrow<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_narrow(serializer)
+    }
+}
+
+struct Wide<T>(T);
+
+impl<'de, T: WideInt> Deserialize<'de> for Wide<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize_narrow(deserializer).map(Wide)
+    }
+}
+
+impl WideInt for i128 {
+    fn serialize_narrow<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        i64::try_from(*self)
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+    fn deserialize_narrow<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(i128::from)
+    }
+}
+
+impl WideInt for u128 {
+    fn serialize_narrow<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        u64::try_from(*self)
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+    fn deserialize_narrow<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u64::deserialize(deserializer).map(u128::from)
+    }
+}
+
+impl<T: WideInt> WideInt for Option<T> {
+    fn serialize_narrow<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_ref().map(Narrow).serialize(serializer)
+    }
+    fn deserialize_narrow<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<Wide<T>>::deserialize(deserializer).map(|v| v.map(|w| w.0))
+    }
+}
+
+impl<T: WideInt> WideInt for Vec<T> {
+    fn serialize_narrow<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Narrow))
+    }
+    fn deserialize_narrow<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Wide<T>>::deserialize(deserializer).map(|v| v.into_iter().map(|w| w.0).collect())
+    }
+}
+
+impl<T: WideInt> WideInt for HashMap<String, T> {
+    fn serialize_narrow<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter().map(|(k, v)| (k, Narrow(v))))
+    }
+    fn deserialize_narrow<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        HashMap::<String, Wide<T>>::deserialize(deserializer)
+            .map(|v| v.into_iter().map(|(k, w)| (k, w.0)).collect())
+    }
+}
+
+pub fn serialize<T: WideInt, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.serialize_narrow(serializer)
+}
+
+pub fn deserialize<'de, T: WideInt, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::deserialize_narrow(deserializer)
+}
@@ -0,0 +1,132 @@
+use crate::db_class::DbClass;
+use crate::db_field::{DbClassSimpleField, DbFieldType};
+
+impl DbClass {
+    pub fn to_ddl(&self) -> Vec<String> {
+        let table = self.ident.table_name();
+        let mut statements = vec![format!(
+            "DEFINE TABLE {} SCHEMALESS{};",
+            table,
+            comment_clause(&self.doc)
+        )];
+        for f in self.simple_fields() {
+            self.field_ddl(&table, &f.name, &f, false, &mut statements);
+        }
+        for f in self.vector_fields() {
+            statements.push(format!(
+                "DEFINE FIELD {} ON TABLE {} TYPE array<float> ASSERT array::len($value) = {}{};",
+                f.name,
+                table,
+                f.dimension,
+                comment_clause(&f.doc)
+            ));
+        }
+        // Links are stored as record ids of the linked class table.
+        for f in self.link_single_fields() {
+            statements.push(format!(
+                "DEFINE FIELD {} ON TABLE {} TYPE record<{}>{};",
+                f.name,
+                table,
+                f.ident.table_name(),
+                comment_clause(&f.doc)
+            ));
+        }
+        for f in self.link_multiple_fields() {
+            statements.push(format!(
+                "DEFINE FIELD {} ON TABLE {} TYPE array<record<{}>>{};",
+                f.name,
+                table,
+                f.ident.table_name(),
+                comment_clause(&f.doc)
+            ));
+        }
+        for f in self.vector_fields() {
+            statements.push(format!(
+                "DEFINE INDEX {} ON TABLE {} FIELDS {} MTREE DIMENSION {} DIST {};",
+                f.index_name(),
+                table,
+                f.name,
+                f.dimension,
+                f.distance.surql_name()
+            ));
+        }
+        if self.history {
+            let history_table = self.ident.history_table_name();
+            statements.push(format!("DEFINE TABLE {} SCHEMALESS;", history_table));
+            statements.push(format!(
+                "DEFINE EVENT history ON TABLE {} THEN (CREATE {} CONTENT {{ record: $before.id OR $after.id, event: $event, at: time::now(), data: $after }});",
+                table, history_table
+            ));
+        }
+        statements
+    }
+
+    fn field_ddl(
+        &self,
+        table: &str,
+        path: &str,
+        f: &DbClassSimpleField,
+        optional: bool,
+        statements: &mut Vec<String>,
+    ) {
+        let mut clauses = vec![];
+        // SurrealDB checks the type before computing VALUE, when a computed field is still unset.
+        let optional = optional || f.value.is_some();
+        if let Some(type_) = f.type_.surql_type() {
+            if optional && !matches!(f.type_, DbFieldType::Option(_)) {
+                clauses.push(format!("TYPE option<{}>", type_));
+            } else {
+                clauses.push(format!("TYPE {}", type_));
+            }
+        }
+        if let Some(default) = f.default.as_ref().and_then(|d| d.surql_default()) {
+            clauses.push(format!("DEFAULT {}", default));
+        }
+        if let Some(value) = &f.value {
+            clauses.push(format!("VALUE {}", value));
+        }
+        let mut asserts = f.type_.surql_assert().into_iter().collect::<Vec<_>>();
+        if self.db_asserts {
+            asserts.extend(f.rules.iter().filter_map(|r| r.surql_assert()));
+        }
+        if !asserts.is_empty() {
+            clauses.push(format!("ASSERT {}", asserts.join(" AND ")));
+        }
+        if let Some(doc) = &f.doc {
+            clauses.push(format!("COMMENT {}", surql_string(doc)));
+        }
+        if !clauses.is_empty() {
+            statements.push(format!(
+                "DEFINE FIELD {} ON TABLE {} {};",
+                path,
+                table,
+                clauses.join(" ")
+            ));
+        }
+        if let Some((ident, suffix, nested_optional)) = f.type_.embedded_path() {
+            for nested in &ident.fields {
+                self.field_ddl(
+                    table,
+                    &format!("{}{}.{}", path, suffix, nested.name),
+                    nested,
+                    optional || nested_optional,
+                    statements,
+                );
+            }
+        }
+    }
+}
+
+fn comment_clause(doc: &Option<String>) -> String {
+    doc.as_ref()
+        .map(|d| format!(" COMMENT {}", surql_string(d)))
+        .unwrap_or_default()
+}
+
+pub(crate) fn surql_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
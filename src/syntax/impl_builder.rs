@@ -2,8 +2,9 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
 use crate::{
-    db_class::{DbClass, DbClassExtension},
-    syntax::string_to_iden,
+    db_class::{DbClass, DbClassExtension, CREATED_AT_VALUE, UPDATED_AT_VALUE, VERSION_VALUE},
+    db_field::{DbClassSimpleField, DbFieldDefault, DbFieldRule, DbFieldType},
+    syntax::{field_iden, serde_crate_tokens, string_to_iden, to_snake_case, to_upper_camel_case},
 };
 
 impl DbClass {
@@ -28,71 +29,786 @@ impl DbClass {
             lm_types,
         ) = self.field_idents();
 
+        let (def_name, def_expr): (Vec<_>, Vec<_>) = self
+            .simple_fields()
+            .into_iter()
+            .filter_map(|f| {
+                let expr = match f.default.as_ref()? {
+                    DbFieldDefault::Literal(v) => {
+                        let literal = f.type_.literal_tokens(v).unwrap();
+                        if f.type_ == DbFieldType::String {
+                            quote! { #literal.to_string() }
+                        } else {
+                            literal
+                        }
+                    }
+                    DbFieldDefault::Rust(v) => syn::parse_str::<TokenStream>(v).unwrap(),
+                    DbFieldDefault::Surql(_) => return None,
+                };
+                Some((field_iden(&f.name), expr))
+            })
+            .unzip();
+
+        let mut_self = if lnk_all_name.is_empty() && lm_all_name.is_empty() && def_name.is_empty() {
+            quote! {}
+        } else {
+            quote! { mut }
+        };
+
+        let resolve_db = if lnk_fetch_name.is_empty() && lm_fetch_name.is_empty() {
+            quote! { _db }
+        } else {
+            quote! { db }
+        };
+
+        let get_active = if self.soft_delete {
+            quote! { Ok(self.with_deleted().db_get(db).await?.filter(|v| v.deleted_at.is_none())) }
+        } else {
+            quote! { self.with_deleted().db_get(db).await }
+        };
+        let get_body = quote! {
+            let Some(deserialized): Option<#deserializer_struct_iden> = db
+                .select(#id_struct_iden::class_hash(), &self.0.id)
+                .await? else {return Ok(None)};
+            deserialized.db_resolve(self.0.id.clone(), db).await
+        };
+        let computed_tokens = self.computed_tokens();
+        let get_tokens = quote! {
+            impl #id_struct_iden {
+                /// Includes soft-deleted records in the following lookup.
+                pub fn with_deleted(&self) -> WithDeleted<#id_struct_iden> {
+                    WithDeleted(self.clone())
+                }
+
+                /// Fetches the record unless it was soft-deleted.
+                pub async fn db_get<B: DbBackend>(&self, db: &B) -> DbResult<Option<#name_iden>> {
+                    #get_active
+                }
+            }
+
+            impl WithDeleted<#id_struct_iden> {
+                /// Fetches the record, including a soft-deleted one.
+                pub async fn db_get<B: DbBackend>(&self, db: &B) -> DbResult<Option<#name_iden>> {
+                    #get_body
+                }
+            }
+        };
+        let delete_tokens = if self.soft_delete {
+            quote! {
+                impl #id_struct_iden {
+                    /// Marks the record as deleted without removing it.
+                    pub async fn db_delete<B: DbBackend>(&self, db: &B) -> DbResult<Option<#id_struct_iden>> {
+                        let id = db.soft_delete(#id_struct_iden::class_hash(), &self.id, #id_struct_iden::computed_fields()).await?;
+                        Ok(id.map(|id| #id_struct_iden { id }))
+                    }
+
+                    /// Clears the deletion mark of a soft-deleted record.
+                    pub async fn db_restore<B: DbBackend>(&self, db: &B) -> DbResult<Option<#id_struct_iden>> {
+                        let id = db.restore(#id_struct_iden::class_hash(), &self.id, #id_struct_iden::computed_fields()).await?;
+                        Ok(id.map(|id| #id_struct_iden { id }))
+                    }
+                }
+            }
+        } else {
+            quote! {
+                impl #id_struct_iden {
+                    /// Removes the record.
+                    pub async fn db_delete<B: DbBackend>(&self, db: &B) -> DbResult<Option<#id_struct_iden>> {
+                        let id = db.delete(#id_struct_iden::class_hash(), &self.id).await?;
+                        Ok(id.map(|id| #id_struct_iden { id }))
+                    }
+                }
+            }
+        };
+
+        let update_tokens = self.update_tokens();
+        let history_tokens = self.history_tokens();
+        let list_tokens = self.list_tokens();
+        let aggregate_tokens = self.aggregate_tokens();
+        let knn_tokens = self.knn_tokens();
+        let validate_value = self.validate_tokens(true);
+        let validate = self.validate_tokens(false);
+
         quote! {
             impl #value_struct_iden {
-                pub async fn db_create(mut self, db: &Surreal<Client>) -> surrealdb::Result<#id_struct_iden> {
+                /// Validates the values and creates a new record, creating linked records first.
+                pub async fn db_create<B: DbBackend>(#mut_self self, db: &B) -> DbResult<#id_struct_iden> {
+                    #(if self.#def_name.is_none() {
+                        self.#def_name = Some(#def_expr);
+                    })*
+                    self.validate()?;
                     #(if let DbLink::New(n) = self.#lnk_all_name {
-                        let result = n.db_create(db).await?;
+                        let result = Box::pin(n.db_create(db)).await?;
                         self.#lnk_all_name = DbLink::Existing(result);
                     };)*
                     #(if let DbLink::New(v) = self.#lm_all_name {
-                        let futures = v.into_iter().map(|n| n.db_create(db)).collect::<Vec<_>>();
+                        let futures = v.into_iter().map(|n| Box::pin(n.db_create(db))).collect::<Vec<_>>();
                         let result = join_all(futures).await.into_iter().collect::<Result<Vec<_>, _>>()?;
                         self.#lm_all_name = DbLink::Existing(result);
                     };)*
-                    let result: Vec<#id_struct_iden> = db.create(#id_struct_iden::class_hash()).content(self).await?;
-                    Ok(result.first().unwrap().clone())
+                    let id = db.create(#id_struct_iden::class_hash(), #id_struct_iden::computed_fields(), self).await?;
+                    Ok(#id_struct_iden { id })
                 }
 
-                pub async fn db_create_get(mut self, db: &Surreal<Client>) -> surrealdb::Result<#name_iden> {
-                    Ok(self.db_create(db).await?.db_get(&db).await?.unwrap())
+                /// Creates the record and fetches it back.
+                pub async fn db_create_get<B: DbBackend>(self, db: &B) -> DbResult<#name_iden> {
+                    Ok(self.db_create(db).await?.db_get(db).await?.unwrap())
                 }
+
+                #validate_value
             }
 
             impl #name_iden {
-                pub async fn db_update(&self, db: &Surreal<Client>) -> surrealdb::Result<Option<#id_struct_iden>> {
-                    db.update((#id_struct_iden::class_hash(), &self.id)).content(#value_struct_iden::from(self.clone())).await
-                }
-                // pub async fn db_update_get(&self, db: &Surreal<Client>) -> surrealdb::Result<Option<#name_iden>> {
-                //     db.update((#db_iden_str, &self.id)).content(#value_struct_iden::from(self.clone())).await
-                // }
+                #update_tokens
+
+                #validate
             }
 
-            impl #id_struct_iden {
-                pub async fn db_get(&self, db: &Surreal<Client>) -> surrealdb::Result<Option<#name_iden>> {
-                    let Some(deserialized): Option<#deserializer_struct_iden> = db
-                        .select((
-                            #id_struct_iden::class_hash(),
-                            &self.id,
-                        ))
-                        .await? else {return Ok(None)};
-                    #(let Some(#lnk_fetch_name) = #lnk_fetch_types{id: deserialized.#lnk_fetch_name.id.to_string()}.db_get(db).await? else {return Ok(None)};)*
-                    #(let #lnk_name = #lnk_types{id: deserialized.#lnk_name.id.to_string()};)*
+            impl #deserializer_struct_iden {
+                /// Resolves the links of a stored record.
+                ///
+                /// Prefetched links include soft-deleted records. A prefetched link to a
+                /// record that no longer exists makes the record unresolvable.
+                pub async fn db_resolve<B: DbBackend>(self, id: String, #resolve_db: &B) -> DbResult<Option<#name_iden>> {
+                    #(let Some(#lnk_fetch_name) = #lnk_fetch_types{id: self.#lnk_fetch_name.id.to_string()}.with_deleted().db_get(db).await? else {return Ok(None)};)*
+                    #(let #lnk_name = #lnk_types{id: self.#lnk_name.id.to_string()};)*
                     #(let Some(#lm_fetch_name) = join_all(
-                        deserialized.#lm_fetch_name
+                        self.#lm_fetch_name
                             .iter()
-                            .map(|i| (|| async {#lm_fetch_types { id: i.id.to_string() }.db_get(db).await})())
+                            .map(|i| async move {#lm_fetch_types { id: i.id.to_string() }.with_deleted().db_get(db).await})
                             .collect::<Vec<_>>()
                     ).await
                     .into_iter()
                     .collect::<Result<Vec<_>, _>>()?
                     .into_iter()
                     .collect::<Option<Vec<_>>>() else {return Ok(None)};)*
-                    #(let #lm_name = deserialized.#lm_name.iter().map(|i| #lm_types{id: i.id.to_string()}).collect();)*
+                    #(let #lm_name = self.#lm_name.iter().map(|i| #lm_types{id: i.id.to_string()}).collect();)*
                     Ok(Some(#name_iden{
-                        id: self.id.clone(),
+                        id,
                         #(#lnk_fetch_name,)*
                         #(#lnk_name,)*
                         #(#lm_fetch_name,)*
                         #(#lm_name,)*
-                        #(#smp_fld: deserialized.#smp_fld,)*
+                        #(#smp_fld: self.#smp_fld,)*
                     }))
                 }
             }
+
+            #knn_tokens
+
+            #history_tokens
+
+            #list_tokens
+
+            #aggregate_tokens
+
+            #get_tokens
+
+            #delete_tokens
+
             impl ClassHash for #id_struct_iden {
                 fn class_hash() -> String {
                     #db_iden_str.to_string()
                 }
+
+                #computed_tokens
+            }
+        }
+    }
+    fn computed_tokens(&self) -> Option<TokenStream> {
+        let computed = self
+            .simple_fields()
+            .into_iter()
+            .filter_map(|f| {
+                let name = f.name.as_str();
+                Some(match (f.value.as_deref(), &f.default) {
+                    (Some(CREATED_AT_VALUE), _) => quote! { DbComputed::CreatedAt(#name) },
+                    (Some(UPDATED_AT_VALUE), _) => quote! { DbComputed::Now(#name) },
+                    (Some(VERSION_VALUE), _) => quote! { DbComputed::Version(#name) },
+                    (Some(_), _) | (None, Some(DbFieldDefault::Surql(_))) => {
+                        quote! { DbComputed::Surql(#name) }
+                    }
+                    _ => return None,
+                })
+            })
+            .collect::<Vec<_>>();
+        (!computed.is_empty()).then(|| {
+            quote! {
+                fn computed_fields() -> &'static [DbComputed] {
+                    &[#(#computed),*]
+                }
+            }
+        })
+    }
+    fn list_tokens(&self) -> TokenStream {
+        let name_iden = string_to_iden(&self.ident.name);
+        let id_struct_iden = string_to_iden(&self.ident.id_struct_name());
+        let deserializer_struct_iden = string_to_iden(&self.ident.serializer_struct_name());
+        let order_by_iden = string_to_iden(&self.ident.order_by_enum_name());
+        let order_by_tokens =
+            order_by_tokens(&self.ident.order_by_enum_name(), self.simple_fields());
+        let conditions = if self.soft_delete {
+            quote! {
+                if query.with_deleted {
+                    vec![]
+                } else {
+                    vec!["deleted_at IS NONE".to_string()]
+                }
+            }
+        } else {
+            quote! { vec![] }
+        };
+        quote! {
+            #order_by_tokens
+
+            impl #name_iden {
+                /// Fetches one page of records.
+                pub async fn db_list(db: &Surreal<Any>, query: DbListQuery<#order_by_iden>) -> DbResult<Page<#name_iden>> {
+                    let conditions = #conditions;
+                    let (records, has_more, total) = db_list_records::<_, DbRecord<#deserializer_struct_iden>>(
+                        db,
+                        vec![#id_struct_iden::class_hash()],
+                        &query,
+                        conditions,
+                    )
+                    .await?;
+                    let next_cursor = db_next_cursor(&query, has_more, #id_struct_iden::class_hash(), records.last());
+                    let items = join_all(
+                        records
+                            .into_iter()
+                            .map(|record| record.value.db_resolve(record.id, db)),
+                    )
+                    .await
+                    .into_iter()
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>();
+                    Ok(Page {
+                        items,
+                        next_cursor,
+                        total,
+                        has_more,
+                    })
+                }
+
+                /// Streams all records, fetching them in batches. A `batch_size` of 0 is treated as 1.
+                pub fn db_stream(db: &Surreal<Any>, batch_size: u64) -> BoxStream<'_, DbResult<#name_iden>> {
+                    let batch_size = batch_size.max(1);
+                    futures::stream::try_unfold(Some(None), move |cursor| async move {
+                        let Some(cursor) = cursor else { return DbResult::Ok(None) };
+                        let page = #name_iden::db_list(db, DbListQuery::cursor(cursor, batch_size)).await?;
+                        let items = futures::stream::iter(page.items.into_iter().map(Ok));
+                        Ok(Some((items, page.next_cursor.map(Some))))
+                    })
+                    .try_flatten()
+                    .boxed()
+                }
+            }
+        }
+    }
+    fn aggregate_tokens(&self) -> TokenStream {
+        let serde_crate = serde_crate_tokens();
+        let name_iden = string_to_iden(&self.ident.name);
+        let id_struct_iden = string_to_iden(&self.ident.id_struct_name());
+        let group_by_iden = string_to_iden(&self.ident.group_by_enum_name());
+        let group_key_iden = string_to_iden(&self.ident.group_key_enum_name());
+        let metric_iden = string_to_iden(&self.ident.metric_enum_name());
+        let aggregate_iden = string_to_iden(&self.ident.aggregate_struct_name());
+        let fields = self.simple_fields();
+
+        let groupable = fields
+            .iter()
+            .filter(|f| f.is_groupable())
+            .collect::<Vec<_>>();
+        let group_variant = groupable
+            .iter()
+            .map(|f| format_ident!("{}", to_upper_camel_case(&f.name)))
+            .collect::<Vec<_>>();
+        let group_field = groupable.iter().map(|f| f.name.clone()).collect::<Vec<_>>();
+        let group_type = groupable
+            .iter()
+            .map(|f| syn::parse_str::<TokenStream>(&f.type_.rust_type()).unwrap())
+            .collect::<Vec<_>>();
+        let (metric_variant, metric_field): (Vec<_>, Vec<_>) = fields
+            .iter()
+            .filter(|f| f.is_numeric())
+            .map(|f| {
+                (
+                    format_ident!("{}", to_upper_camel_case(&f.name)),
+                    f.name.clone(),
+                )
+            })
+            .unzip();
+
+        let (with_deleted_field, with_deleted_fn, filter) = if self.soft_delete {
+            (
+                quote! { with_deleted: bool, },
+                quote! {
+                    /// Includes soft-deleted records in the aggregation.
+                    pub fn with_deleted(mut self) -> Self {
+                        self.with_deleted = true;
+                        self
+                    }
+                },
+                quote! {
+                    if self.with_deleted { "" } else { " WHERE deleted_at IS NONE" }
+                },
+            )
+        } else {
+            (quote! {}, quote! {}, quote! { "" })
+        };
+
+        quote! {
+            /// Fields an aggregation can be grouped by.
+            #[derive(Debug, Clone, Copy)]
+            pub enum #group_by_iden {
+                #(#group_variant,)*
+            }
+
+            impl #group_by_iden {
+                fn field(&self) -> &'static str {
+                    match *self {
+                        #(#group_by_iden::#group_variant => #group_field,)*
+                    }
+                }
+            }
+
+            /// Value of the group an aggregated row belongs to.
+            #[derive(Debug, Clone, Deserialize)]
+            #serde_crate
+            pub enum #group_key_iden {
+                #(#group_variant(#group_type),)*
+            }
+
+            /// Numeric fields an aggregation can summarize.
+            #[derive(Debug, Clone, Copy)]
+            pub enum #metric_iden {
+                #(#metric_variant,)*
+            }
+
+            impl #metric_iden {
+                fn field(&self) -> &'static str {
+                    match *self {
+                        #(#metric_iden::#metric_variant => #metric_field,)*
+                    }
+                }
+            }
+
+            /// Builder for aggregation queries.
+            #[derive(Debug, Clone, Default)]
+            pub struct #aggregate_iden {
+                group_by: Option<#group_by_iden>,
+                metric: Option<#metric_iden>,
+                #with_deleted_field
+            }
+
+            impl #aggregate_iden {
+                /// Groups the rows by the given field.
+                pub fn group_by(mut self, group_by: #group_by_iden) -> Self {
+                    self.group_by = Some(group_by);
+                    self
+                }
+
+                /// Computes sum, average, minimum and maximum of the given field.
+                pub fn metric(mut self, metric: #metric_iden) -> Self {
+                    self.metric = Some(metric);
+                    self
+                }
+
+                #with_deleted_fn
+
+                /// Runs the aggregation.
+                pub async fn fetch(self, db: &Surreal<Any>) -> DbResult<Vec<DbAggregate<#group_key_iden>>> {
+                    let mut fields = vec!["count() AS count".to_string()];
+                    if let Some(metric) = self.metric {
+                        let f = metric.field();
+                        fields.push(format!(
+                            "math::sum({f}) AS sum, math::mean({f}) AS avg, math::min({f}) AS min, math::max({f}) AS max"
+                        ));
+                    }
+                    let group = match self.group_by {
+                        Some(group_by) => {
+                            fields.insert(0, format!("{} AS key", group_by.field()));
+                            "GROUP BY key"
+                        }
+                        None => "GROUP ALL",
+                    };
+                    let sql = format!(
+                        "SELECT {} FROM type::table($table){} {}",
+                        fields.join(", "),
+                        #filter,
+                        group
+                    );
+                    let mut response = db
+                        .query(sql)
+                        .bind(("table", #id_struct_iden::class_hash()))
+                        .await?;
+                    Ok(match self.group_by {
+                        None => response.take(0)?,
+                        #(Some(#group_by_iden::#group_variant) => response
+                            .take::<Vec<DbAggregate<#group_type>>>(0)?
+                            .into_iter()
+                            .map(|row| row.map_key(#group_key_iden::#group_variant))
+                            .collect(),)*
+                    })
+                }
+            }
+
+            impl #name_iden {
+                /// Starts an aggregation query.
+                pub fn db_aggregate() -> #aggregate_iden {
+                    #aggregate_iden::default()
+                }
+            }
+        }
+    }
+    fn history_tokens(&self) -> TokenStream {
+        let serde_crate = serde_crate_tokens();
+        if !self.history {
+            return quote! {};
+        }
+        let name_iden = string_to_iden(&self.ident.name);
+        let id_struct_iden = string_to_iden(&self.ident.id_struct_name());
+        let deserializer_struct_iden = string_to_iden(&self.ident.serializer_struct_name());
+        let history_iden = string_to_iden(&self.ident.history_struct_name());
+        let row_iden = format_ident!("{}Row", self.ident.history_struct_name());
+        let history_table = self.ident.history_table_hash();
+        quote! {
+            /// One recorded change of a record.
+            #[derive(Debug, Clone)]
+            pub struct #history_iden {
+                pub event: String,
+                pub at: surrealdb::sql::Datetime,
+                pub value: Option<#name_iden>,
+            }
+
+            #[derive(Deserialize)]
+            #serde_crate
+            struct #row_iden {
+                event: String,
+                at: surrealdb::sql::Datetime,
+                data: Option<#deserializer_struct_iden>,
+            }
+
+            impl #row_iden {
+                async fn db_resolve(self, id: String, db: &Surreal<Any>) -> DbResult<#history_iden> {
+                    let value = match self.data {
+                        Some(data) => data.db_resolve(id, db).await?,
+                        None => None,
+                    };
+                    Ok(#history_iden {
+                        event: self.event,
+                        at: self.at,
+                        value,
+                    })
+                }
+            }
+
+            impl #id_struct_iden {
+                /// Fetches all recorded changes of the record, oldest first.
+                pub async fn db_history(&self, db: &Surreal<Any>) -> DbResult<Vec<#history_iden>> {
+                    let rows: Vec<#row_iden> = db
+                        .query("SELECT event, at, data FROM type::table($table) WHERE record = $record ORDER BY at")
+                        .bind(("table", #history_table))
+                        .bind(("record", Thing::from(self.clone())))
+                        .await?
+                        .take(0)?;
+                    let mut history = Vec::with_capacity(rows.len());
+                    for row in rows {
+                        history.push(row.db_resolve(self.id.clone(), db).await?);
+                    }
+                    Ok(history)
+                }
+
+                /// Fetches the record as it was at the given time, from its history.
+                pub async fn db_get_as_of(&self, db: &Surreal<Any>, at: surrealdb::sql::Datetime) -> DbResult<Option<#name_iden>> {
+                    let row: Option<#row_iden> = db
+                        .query("SELECT event, at, data FROM type::table($table) WHERE record = $record AND at <= $at ORDER BY at DESC LIMIT 1")
+                        .bind(("table", #history_table))
+                        .bind(("record", Thing::from(self.clone())))
+                        .bind(("at", at))
+                        .await?
+                        .take(0)?;
+                    let Some(row) = row else { return Ok(None) };
+                    Ok(row.db_resolve(self.id.clone(), db).await?.value)
+                }
+            }
+        }
+    }
+    fn update_tokens(&self) -> TokenStream {
+        let name_iden = string_to_iden(&self.ident.name);
+        let id_struct_iden = string_to_iden(&self.ident.id_struct_name());
+        let value_struct_iden = string_to_iden(&self.ident.value_struct_name());
+        let deserializer_struct_iden = string_to_iden(&self.ident.serializer_struct_name());
+        let active = self.soft_delete;
+        let guard = if self.versioned {
+            quote! { DbGuard { active: #active, version: Some(self.version) } }
+        } else {
+            quote! { DbGuard { active: #active, version: None } }
+        };
+        if !self.versioned {
+            return quote! {
+                /// Validates the record and stores its current values.
+                pub async fn db_update<B: DbBackend>(&self, db: &B) -> DbResult<Option<#id_struct_iden>> {
+                    self.validate()?;
+                    let updated = db
+                        .update(#id_struct_iden::class_hash(), &self.id, #id_struct_iden::computed_fields(), #value_struct_iden::from(self.clone()), #guard)
+                        .await?;
+                    Ok(updated.map(|u| #id_struct_iden { id: u.id }))
+                }
+
+                /// Stores the fields present in the patch, keeping the others, and fetches the
+                /// record back. Field rules are not checked for the patch.
+                pub async fn db_merge<B: DbBackend, P: Serialize + Send + 'static>(&self, db: &B, patch: P) -> DbResult<Option<#name_iden>> {
+                    let Some(updated) = db.merge(#id_struct_iden::class_hash(), &self.id, #id_struct_iden::computed_fields(), patch, #guard).await? else {
+                        return Ok(None);
+                    };
+                    #id_struct_iden { id: updated.id }.db_get(db).await
+                }
+            };
+        }
+        let existing = if self.soft_delete {
+            quote! { Some(existing) if existing.deleted_at.is_none() }
+        } else {
+            quote! { Some(_) }
+        };
+        let conflict = quote! {
+            let existing: Option<#deserializer_struct_iden> = db.select(#id_struct_iden::class_hash(), &self.id).await?;
+            match existing {
+                #existing => Err(DbError::Conflict(self.id.clone())),
+                _ => Ok(None),
+            }
+        };
+        quote! {
+            /// Validates the record and stores its current values if its version is unchanged,
+            /// then takes over the new version.
+            pub async fn db_update<B: DbBackend>(&mut self, db: &B) -> DbResult<Option<#id_struct_iden>> {
+                self.validate()?;
+                let updated = db
+                    .update(#id_struct_iden::class_hash(), &self.id, #id_struct_iden::computed_fields(), #value_struct_iden::from(self.clone()), #guard)
+                    .await?;
+                if let Some(updated) = updated {
+                    self.version = updated.version.unwrap_or(self.version);
+                    return Ok(Some(#id_struct_iden { id: updated.id }));
+                }
+                #conflict
+            }
+
+            /// Stores the fields present in the patch, keeping the others, if the version of
+            /// the record is unchanged, and fetches the record back. Field rules are not
+            /// checked for the patch.
+            pub async fn db_merge<B: DbBackend, P: Serialize + Send + 'static>(&self, db: &B, patch: P) -> DbResult<Option<#name_iden>> {
+                if let Some(updated) = db.merge(#id_struct_iden::class_hash(), &self.id, #id_struct_iden::computed_fields(), patch, #guard).await? {
+                    return #id_struct_iden { id: updated.id }.db_get(db).await;
+                }
+                #conflict
+            }
+        }
+    }
+    fn validate_tokens(&self, value_struct: bool) -> TokenStream {
+        let checks = self
+            .simple_fields()
+            .into_iter()
+            .filter(|f| !f.is_readonly() && !f.rules.is_empty())
+            .map(|f| {
+                let name = field_iden(&f.name);
+                let field = f.name.clone();
+                let rules = f
+                    .rules
+                    .iter()
+                    .map(|r| {
+                        let error = |message: TokenStream| {
+                            quote! {
+                                errors.push(DbValidationError {
+                                    field: #field.to_string(),
+                                    message: #message,
+                                });
+                            }
+                        };
+                        let (failed, message) = match r {
+                            DbFieldRule::Min(v) => {
+                                let lit = f.type_.literal_tokens(v).unwrap();
+                                (quote! { *value < #lit }, format!("must be at least {}", v))
+                            }
+                            DbFieldRule::Max(v) => {
+                                let lit = f.type_.literal_tokens(v).unwrap();
+                                (quote! { *value > #lit }, format!("must be at most {}", v))
+                            }
+                            DbFieldRule::MinLength(v) => (
+                                quote! { value.chars().count() < #v },
+                                format!("must be at least {} long", v),
+                            ),
+                            DbFieldRule::MaxLength(v) => (
+                                quote! { value.chars().count() > #v },
+                                format!("must be at most {} long", v),
+                            ),
+                            DbFieldRule::Regex(v) => {
+                                let message = format!("must match {}", v);
+                                let push = error(quote! { #message.to_string() });
+                                return quote! {
+                                    {
+                                        static PATTERN: std::sync::OnceLock<db_engine::runtime::v1::regex::Regex> = std::sync::OnceLock::new();
+                                        let pattern = PATTERN.get_or_init(|| db_engine::runtime::v1::regex::Regex::new(#v).unwrap());
+                                        if !pattern.is_match(value) {
+                                            #push
+                                        }
+                                    }
+                                };
+                            }
+                            DbFieldRule::OneOf(v) => {
+                                let lits = v
+                                    .iter()
+                                    .map(|l| f.type_.literal_tokens(l).unwrap())
+                                    .collect::<Vec<_>>();
+                                (
+                                    quote! { ![#(#lits),*].iter().any(|v| *value == *v) },
+                                    format!("must be one of {}", v.join(", ")),
+                                )
+                            }
+                            DbFieldRule::Custom(path) => {
+                                let path = syn::parse_str::<TokenStream>(path).unwrap();
+                                let push = error(quote! { message });
+                                return quote! {
+                                    if let Err(message) = #path(value) {
+                                        #push
+                                    }
+                                };
+                            }
+                        };
+                        let push = error(quote! { #message.to_string() });
+                        quote! {
+                            if #failed {
+                                #push
+                            }
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                if value_struct && f.default.is_some() {
+                    quote! {
+                        if let Some(value) = &self.#name {
+                            #(#rules)*
+                        }
+                    }
+                } else {
+                    quote! {
+                        {
+                            let value = &self.#name;
+                            #(#rules)*
+                        }
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        if checks.is_empty() {
+            return quote! {
+                /// Checks the field rules.
+                pub fn validate(&self) -> Result<(), Vec<DbValidationError>> {
+                    Ok(())
+                }
+            };
+        }
+        quote! {
+            /// Checks the field rules.
+            pub fn validate(&self) -> Result<(), Vec<DbValidationError>> {
+                let mut errors = vec![];
+                #(#checks)*
+                if errors.is_empty() {
+                    Ok(())
+                } else {
+                    Err(errors)
+                }
+            }
+        }
+    }
+    fn knn_tokens(&self) -> TokenStream {
+        let serde_crate = serde_crate_tokens();
+        let name_iden = string_to_iden(&self.ident.name);
+        let id_struct_iden = string_to_iden(&self.ident.id_struct_name());
+        let deserializer_struct_iden = string_to_iden(&self.ident.serializer_struct_name());
+        let filter = if self.soft_delete {
+            " AND deleted_at IS NONE"
+        } else {
+            ""
+        };
+        // The KNN operator picks its candidates before other conditions apply, so it has to
+        // fetch one extra candidate for every soft-deleted record.
+        let candidates = if self.soft_delete {
+            quote! {
+                let deleted: Option<usize> = db
+                    .query("RETURN count(SELECT VALUE id FROM type::table($table) WHERE deleted_at IS NOT NONE)")
+                    .bind(("table", #id_struct_iden::class_hash()))
+                    .await?
+                    .take(0)?;
+                let candidates = k + deleted.unwrap_or(0);
+            }
+        } else {
+            quote! { let candidates = k; }
+        };
+        let vector_fields = self.vector_fields();
+        let methods = vector_fields
+            .iter()
+            .map(|f| {
+                let method_name = if vector_fields.len() == 1 {
+                    format_ident!("db_knn")
+                } else {
+                    format_ident!("db_knn_{}", to_snake_case(&f.name))
+                };
+                let field = f.name.clone();
+                let dimension = f.dimension;
+                let message = format!("must have {} dimensions", dimension);
+                let query = format!(
+                    "SELECT *, {} AS distance FROM type::table($table) WHERE {} <|{{}}|> $vector{} ORDER BY distance LIMIT {{}}",
+                    f.distance.surql_distance(&f.name, "$vector"),
+                    f.name,
+                    filter
+                );
+                quote! {
+                    /// Finds the records nearest to the given vector with the field's vector index.
+                    pub async fn #method_name(db: &Surreal<Any>, vector: &[f32], k: usize) -> DbResult<Vec<(#name_iden, f32)>> {
+                        if vector.len() != #dimension {
+                            return Err(DbError::Validation(vec![DbValidationError {
+                                field: #field.to_string(),
+                                message: #message.to_string(),
+                            }]));
+                        }
+                        #[derive(Deserialize)]
+                        #serde_crate
+                        struct Row {
+                            #[serde(deserialize_with = "thing_to_string")]
+                            id: String,
+                            distance: f32,
+                            #[serde(flatten)]
+                            value: #deserializer_struct_iden,
+                        }
+                        #candidates
+                        let rows: Vec<Row> = db
+                            .query(format!(#query, candidates, k))
+                            .bind(("table", #id_struct_iden::class_hash()))
+                            .bind(("vector", vector.to_vec()))
+                            .await?
+                            .take(0)?;
+                        let resolved = join_all(rows.into_iter().map(|row| async move {
+                            let value = row.value.db_resolve(row.id, db).await?;
+                            DbResult::Ok(value.map(|v| (v, row.distance)))
+                        }))
+                        .await;
+                        Ok(resolved
+                            .into_iter()
+                            .collect::<Result<Vec<_>, _>>()?
+                            .into_iter()
+                            .flatten()
+                            .collect())
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        if methods.is_empty() {
+            return quote! {};
+        }
+        quote! {
+            impl #name_iden {
+                #(#methods)*
             }
         }
     }
@@ -113,6 +829,7 @@ impl DbClass {
         }
         let name_iden = string_to_iden(&self.ident.name);
         let ext_name = string_to_iden(&ext.0);
+        let ext_field = field_iden(&ext.0);
         let (common_fields, extend_fields): (Vec<_>, Vec<_>) = ext
             .1
             .simple_fields()
@@ -120,17 +837,17 @@ impl DbClass {
             .partition(|f| self.simple_fields().contains(f));
         let cmn_f = common_fields
             .into_iter()
-            .map(|f| format_ident!("{}", f.name))
+            .map(|f| field_iden(&f.name))
             .collect::<Vec<_>>();
         let ext_f = extend_fields
             .into_iter()
-            .map(|f| format_ident!("{}", f.name))
+            .map(|f| field_iden(&f.name))
             .collect::<Vec<_>>();
         quote! {
             #[async_trait]
             impl DbExtend<#ext_name> for #name_iden {
-                async fn db_extend(self, db: &Surreal<Client>) -> surrealdb::Result<#ext_name> {
-                    let base = self.#ext_name.db_get(db).await?.unwrap();
+                async fn db_extend<B: DbBackend>(self, db: &B) -> DbResult<#ext_name> {
+                    let base = self.#ext_field.db_get(db).await?.unwrap();
                     Ok(#ext_name {
                         #(#cmn_f: self.#cmn_f,)*
                         #(#ext_f: base.#ext_f,)*
@@ -145,12 +862,12 @@ impl DbClass {
         let smp_fld = self
             .simple_fields()
             .into_iter()
-            .map(|f| format_ident!("{}", f.name))
+            .map(|f| field_iden(&f.name))
             .collect::<Vec<_>>();
         quote! {
             #[async_trait]
             impl DbExtend<#ext_name> for #name_iden {
-                async fn db_extend(self, db: &Surreal<Client>) -> surrealdb::Result<#ext_name> {
+                async fn db_extend<B: DbBackend>(self, _db: &B) -> DbResult<#ext_name> {
                     Ok(#ext_name {
                         #(#smp_fld: self.#smp_fld, )*
                     })
@@ -158,6 +875,51 @@ impl DbClass {
             }
         }
     }
+    pub fn to_repository_tokens(&self) -> TokenStream {
+        let name_iden = string_to_iden(&self.ident.name);
+        let id_struct_iden = string_to_iden(&self.ident.id_struct_name());
+        let value_struct_iden = string_to_iden(&self.ident.value_struct_name());
+        let serializer_struct_iden = string_to_iden(&self.ident.serializer_struct_name());
+        let order_by_iden = string_to_iden(&self.ident.order_by_enum_name());
+        quote! {
+            impl Entity for #name_iden {
+                type Id = #id_struct_iden;
+                type Value = #value_struct_iden;
+                type Serializer = #serializer_struct_iden;
+                type OrderBy = #order_by_iden;
+
+                fn id(&self) -> #id_struct_iden {
+                    #id_struct_iden { id: self.id.clone() }
+                }
+            }
+
+            #[async_trait]
+            impl Repository for #name_iden {
+                async fn create<B: DbBackend>(db: &B, value: #value_struct_iden) -> DbResult<#id_struct_iden> {
+                    value.db_create(db).await
+                }
+                async fn create_get<B: DbBackend>(db: &B, value: #value_struct_iden) -> DbResult<#name_iden> {
+                    value.db_create_get(db).await
+                }
+                async fn get<B: DbBackend>(db: &B, id: &#id_struct_iden) -> DbResult<Option<#name_iden>> {
+                    id.db_get(db).await
+                }
+                async fn update<B: DbBackend>(&mut self, db: &B) -> DbResult<Option<#id_struct_iden>> {
+                    self.db_update(db).await
+                }
+                async fn delete<B: DbBackend>(db: &B, id: &#id_struct_iden) -> DbResult<Option<#id_struct_iden>> {
+                    id.db_delete(db).await
+                }
+                async fn list(db: &Surreal<Any>, query: DbListQuery<#order_by_iden>) -> DbResult<Page<#name_iden>> {
+                    #name_iden::db_list(db, query).await
+                }
+                async fn resolve<B: DbBackend>(serializer: #serializer_struct_iden, id: String, db: &B) -> DbResult<Option<#name_iden>> {
+                    serializer.db_resolve(id, db).await
+                }
+            }
+        }
+    }
+
     pub fn to_impl_from_tokens(&self) -> TokenStream {
         let name_iden = string_to_iden(&self.ident.name);
         let id_struct_iden = string_to_iden(&self.ident.id_struct_name());
@@ -166,7 +928,7 @@ impl DbClass {
         let extensions_tokens = self.extensions_tokens();
 
         let (
-            smp_fld,
+            _smp_fld,
             lnk_fetch_name,
             lnk_name,
             _lnk_all_name,
@@ -178,11 +940,28 @@ impl DbClass {
             lm_fetch_types,
             _lm_types,
         ) = self.field_idents();
+        let smp_value = self
+            .simple_fields()
+            .into_iter()
+            .filter(|f| !f.is_readonly())
+            .map(|f| {
+                let name = field_iden(&f.name);
+                if f.default.is_some() {
+                    quote! { #name: Some(value.#name) }
+                } else {
+                    quote! { #name: value.#name }
+                }
+            })
+            .chain(self.vector_fields().into_iter().map(|f| {
+                let name = field_iden(&f.name);
+                quote! { #name: value.#name }
+            }))
+            .collect::<Vec<_>>();
         quote! {
             impl From<#name_iden> for #value_struct_iden {
                 fn from(value: #name_iden) -> Self {
                     #value_struct_iden {
-                        #(#smp_fld: value.#smp_fld,)*
+                        #(#smp_value,)*
                         #(#lnk_fetch_name: DbLink::Existing(#lnk_fetch_types{id: value.#lnk_fetch_name.id}), )*
                         #(#lnk_name: DbLink::Existing(value.#lnk_name), )*
                         #(#lm_fetch_name: DbLink::Existing(value.#lm_fetch_name.into_iter().map(|i| #lm_fetch_types{id: i.id}).collect()), )*
@@ -199,9 +978,9 @@ impl DbClass {
                 }
             }
 
-            impl Into<Thing> for #id_struct_iden {
-                fn into(self) -> Thing {
-                    Thing::from((#id_struct_iden::class_hash(), self.id))
+            impl From<#id_struct_iden> for Thing {
+                fn from(value: #id_struct_iden) -> Self {
+                    Thing::from((#id_struct_iden::class_hash(), value.id))
                 }
             }
 
@@ -209,6 +988,7 @@ impl DbClass {
 
         }
     }
+    #[allow(clippy::type_complexity)]
     fn field_idents(
         &self,
     ) -> (
@@ -227,7 +1007,9 @@ impl DbClass {
         let smp_fld = self
             .simple_fields()
             .into_iter()
-            .map(|f| format_ident!("{}", f.name))
+            .map(|f| f.name)
+            .chain(self.vector_fields().into_iter().map(|f| f.name))
+            .map(|f| field_iden(&f))
             .collect::<Vec<_>>();
 
         let (lnk_fetch, lnk): (Vec<_>, Vec<_>) = self
@@ -236,12 +1018,9 @@ impl DbClass {
             .partition(|i| i.prefetch);
         let lnk_fetch_name = lnk_fetch
             .iter()
-            .map(|f| format_ident!("{}", f.name))
-            .collect::<Vec<_>>();
-        let lnk_name = lnk
-            .iter()
-            .map(|f| format_ident!("{}", f.name))
+            .map(|f| field_iden(&f.name))
             .collect::<Vec<_>>();
+        let lnk_name = lnk.iter().map(|f| field_iden(&f.name)).collect::<Vec<_>>();
         let lnk_all_name = lnk_name
             .iter()
             .chain(lnk_fetch_name.iter())
@@ -262,12 +1041,9 @@ impl DbClass {
             .partition(|i| i.prefetch);
         let lm_fetch_name = lm_fetch
             .iter()
-            .map(|f| format_ident!("{}", f.name))
-            .collect::<Vec<_>>();
-        let lm_name = lm
-            .iter()
-            .map(|f| format_ident!("{}", f.name))
+            .map(|f| field_iden(&f.name))
             .collect::<Vec<_>>();
+        let lm_name = lm.iter().map(|f| field_iden(&f.name)).collect::<Vec<_>>();
         let lm_all_name = lm_name
             .iter()
             .chain(lm_fetch_name.iter())
@@ -296,3 +1072,46 @@ impl DbClass {
         )
     }
 }
+
+/// Generates the order-by enum offering `Id` and every orderable field.
+pub(crate) fn order_by_tokens(name: &str, fields: Vec<DbClassSimpleField>) -> TokenStream {
+    let order_by_iden = string_to_iden(name);
+    let orderable = fields
+        .into_iter()
+        .filter(|f| f.name != "id" && f.is_orderable())
+        .collect::<Vec<_>>();
+    let order_variant = orderable
+        .iter()
+        .map(|f| format_ident!("{}", to_upper_camel_case(&f.name)))
+        .collect::<Vec<_>>();
+    let order_field = orderable.iter().map(|f| f.name.clone()).collect::<Vec<_>>();
+    let order_type = orderable
+        .iter()
+        .map(|f| f.type_.surql_type().unwrap())
+        .collect::<Vec<_>>();
+    quote! {
+        /// Fields a list can be ordered by.
+        #[derive(Debug, Clone, Copy, Default)]
+        pub enum #order_by_iden {
+            #[default]
+            Id,
+            #(#order_variant,)*
+        }
+
+        impl DbOrderBy for #order_by_iden {
+            fn field(&self) -> &'static str {
+                match self {
+                    #order_by_iden::Id => "id",
+                    #(#order_by_iden::#order_variant => #order_field,)*
+                }
+            }
+
+            fn surql_type(&self) -> &'static str {
+                match self {
+                    #order_by_iden::Id => "record",
+                    #(#order_by_iden::#order_variant => #order_type,)*
+                }
+            }
+        }
+    }
+}
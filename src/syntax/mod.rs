@@ -1,8 +1,134 @@
-use proc_macro2::{Ident, Span};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
 
+use crate::db_class::DbStruct;
+
+pub mod ddl_builder;
 pub mod impl_builder;
 pub mod struct_builder;
 
 fn string_to_iden(str: &str) -> Ident {
-    syn::Ident::new(&str, Span::call_site())
+    syn::Ident::new(str, Span::call_site())
+}
+
+fn to_upper_camel_case(str: &str) -> String {
+    str.split('_')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let mut chars = s.chars();
+            match chars.next() {
+                Some(c) => c.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn to_snake_case(str: &str) -> String {
+    let chars = str.chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev = i.checked_sub(1).map(|p| chars[p]);
+            let next = chars.get(i + 1);
+            let boundary = prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit())
+                || (prev.is_some_and(|p| p.is_uppercase())
+                    && next.is_some_and(|n| n.is_lowercase()));
+            if boundary && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(*c);
+        }
+    }
+    snake
+}
+
+/// Extra derives and attributes configured for `target`, skipping derives the type always has.
+pub(crate) fn struct_config_tokens(
+    derives: &[(DbStruct, String)],
+    attributes: &[(DbStruct, String)],
+    target: DbStruct,
+    builtin: &[&str],
+) -> (Vec<TokenStream>, Vec<TokenStream>) {
+    let mut seen = vec![];
+    let derives = derives
+        .iter()
+        .filter(|(t, d)| *t == target && !builtin.contains(&d.as_str()))
+        .filter(|(_, d)| {
+            let new = !seen.contains(d);
+            seen.push(d.clone());
+            new
+        })
+        .map(|(_, d)| syn::parse_str::<TokenStream>(d).unwrap())
+        .collect();
+    let attributes = attributes
+        .iter()
+        .filter(|(t, _)| *t == target)
+        .map(|(_, a)| a.parse::<TokenStream>().unwrap())
+        .collect();
+    (derives, attributes)
+}
+
+/// Points serde derives at the runtime re-export, so generated code needs no direct serde dependency.
+pub(crate) fn serde_crate_tokens() -> TokenStream {
+    quote! { #[serde(crate = "db_engine::runtime::v1::serde")] }
+}
+
+pub(crate) fn field_iden(str: &str) -> Ident {
+    let name = to_snake_case(str);
+    if is_rust_keyword(&name) && !matches!(name.as_str(), "self" | "Self" | "super" | "crate") {
+        Ident::new_raw(&name, Span::call_site())
+    } else {
+        string_to_iden(&name)
+    }
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+pub(crate) fn is_rust_keyword(str: &str) -> bool {
+    RUST_KEYWORDS.contains(&str)
+}
+
+pub(crate) fn doc_tokens(doc: &Option<String>) -> TokenStream {
+    let lines = doc
+        .iter()
+        .flat_map(|d| d.lines())
+        .map(|l| format!(" {}", l))
+        .collect::<Vec<_>>();
+    quote! {
+        #(#[doc = #lines])*
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snake_case_splits_words_and_acronyms() {
+        assert_eq!(to_snake_case("name"), "name");
+        assert_eq!(to_snake_case("firstName"), "first_name");
+        assert_eq!(to_snake_case("FirstName"), "first_name");
+        assert_eq!(to_snake_case("HTTPServer"), "http_server");
+        assert_eq!(to_snake_case("userID"), "user_id");
+        assert_eq!(to_snake_case("address2Line"), "address2_line");
+        assert_eq!(to_snake_case("Foo_Bar"), "foo_bar");
+        assert_eq!(to_snake_case("already_snake"), "already_snake");
+    }
+
+    #[test]
+    fn field_idents_escape_keywords() {
+        assert_eq!(field_iden("firstName").to_string(), "first_name");
+        assert_eq!(field_iden("loop").to_string(), "r#loop");
+        assert_eq!(field_iden("Match").to_string(), "r#match");
+        assert_eq!(field_iden("async").to_string(), "r#async");
+    }
 }
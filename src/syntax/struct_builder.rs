@@ -1,12 +1,14 @@
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::{format_ident, quote, ToTokens};
+use quote::{quote, ToTokens};
 
-use crate::db_class::DbClass;
+use crate::db_class::{DbClass, DbStruct};
+use crate::syntax::{doc_tokens, field_iden, serde_crate_tokens, to_snake_case};
 
 pub struct Field {
     name: String,
     field_type: String,
     decorators: Vec<String>,
+    doc: Option<String>,
 }
 
 impl Field {
@@ -19,14 +21,22 @@ impl Field {
             name: name.into(),
             field_type: field_type.into(),
             decorators: decorators.into_iter().map(|d| d.into()).collect(),
+            doc: None,
         }
     }
+    pub fn with_doc(mut self, doc: Option<String>) -> Self {
+        self.doc = doc;
+        self
+    }
     pub fn new(name: impl Into<String>, field_type: impl Into<String>) -> Self {
         Field::with_decorators(name, field_type, vec![] as Vec<String>)
     }
     pub fn to_tokens(&self) -> TokenStream {
-        let name_iden = format_ident!("{}", self.name);
-        // let type_iden = format_ident!("{}", self.field_type);
+        let name_iden = field_iden(&self.name);
+        let rename = (to_snake_case(&self.name) != self.name).then(|| {
+            let name = &self.name;
+            quote! { #[serde(rename = #name)] }
+        });
         let type_iden: TokenStream = syn::parse_str::<TokenStream>(&self.field_type)
             .unwrap()
             .into_token_stream();
@@ -35,7 +45,10 @@ impl Field {
             .iter()
             .map(|d| d.parse::<TokenStream>().unwrap())
             .collect::<Vec<_>>();
+        let doc = doc_tokens(&self.doc);
         quote! {
+            #doc
+            #rename
             #(#decorators)*
             pub #name_iden: #type_iden
         }
@@ -44,16 +57,33 @@ impl Field {
 
 pub struct StructSyntaxBuilder {
     name: String,
-    hash: String,
+    hash: Option<String>,
     fields: Vec<Field>,
+    derives: Vec<String>,
+    attributes: Vec<String>,
+    doc: Option<String>,
 }
 
 impl StructSyntaxBuilder {
     pub fn new(name: impl Into<String>, hash: impl Into<String>) -> Self {
         StructSyntaxBuilder {
             name: name.into(),
-            hash: hash.into(),
+            hash: Some(hash.into()),
+            fields: Vec::new(),
+            derives: Vec::new(),
+            attributes: Vec::new(),
+            doc: None,
+        }
+    }
+
+    pub fn plain(name: impl Into<String>) -> Self {
+        StructSyntaxBuilder {
+            name: name.into(),
+            hash: None,
             fields: Vec::new(),
+            derives: Vec::new(),
+            attributes: Vec::new(),
+            doc: None,
         }
     }
 
@@ -62,19 +92,60 @@ impl StructSyntaxBuilder {
         self
     }
 
+    pub fn with_doc(&mut self, doc: Option<String>) -> &mut Self {
+        self.doc = doc;
+        self
+    }
+
+    pub fn add_derive(&mut self, derive: impl Into<String>) -> &mut Self {
+        let derive = derive.into();
+        if !["Debug", "Serialize", "Deserialize", "Clone"].contains(&derive.as_str())
+            && !self.derives.contains(&derive)
+        {
+            self.derives.push(derive);
+        }
+        self
+    }
+
+    pub fn add_attribute(&mut self, attribute: impl Into<String>) -> &mut Self {
+        self.attributes.push(attribute.into());
+        self
+    }
+
     fn name_iden(&self) -> Ident {
         syn::Ident::new(&self.name, Span::call_site())
     }
 
     pub fn to_tokens(&self) -> TokenStream {
+        let serde_crate = serde_crate_tokens();
         let name_iden = self.name_iden();
         let field_defs: Vec<_> = self.fields.iter().map(Field::to_tokens).collect();
-        let hash = &self.hash;
+        let tag = self.hash.as_ref().map(|hash| {
+            quote! {
+                #[serde(tag = "type")]
+                #[serde(rename = #hash)]
+            }
+        });
+
+        let derives = self
+            .derives
+            .iter()
+            .map(|d| syn::parse_str::<TokenStream>(d).unwrap())
+            .collect::<Vec<_>>();
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|a| a.parse::<TokenStream>().unwrap())
+            .collect::<Vec<_>>();
+
+        let doc = doc_tokens(&self.doc);
 
         quote! {
-            #[derive(Debug, Serialize, Deserialize, Clone)]
-            #[serde(tag = "type")]
-            #[serde(rename = #hash)]
+            #doc
+            #[derive(Debug, Serialize, Deserialize, Clone #(, #derives)*)]
+            #serde_crate
+            #tag
+            #(#attributes)*
             pub struct #name_iden {
                 #(#field_defs,)*
             }
@@ -86,28 +157,56 @@ impl DbClass {
     pub fn to_main_builder(&self) -> StructSyntaxBuilder {
         let mut builder = self.id_builder(&self.ident.name);
         builder = self.add_simple_fields(builder);
+        builder = self.add_vector_fields(builder);
         builder = self.add_link_single_fields(builder);
         builder = self.add_link_multiple_fields(builder);
-        builder
+        self.add_struct_attributes(builder, DbStruct::Main)
     }
 
     pub fn to_id_builder(&self) -> StructSyntaxBuilder {
-        self.id_builder(&self.ident.id_struct_name())
+        let builder = self.id_builder(&self.ident.id_struct_name());
+        self.add_struct_attributes(builder, DbStruct::Id)
     }
     pub fn to_value_builder(&self) -> StructSyntaxBuilder {
         let mut builder =
-            StructSyntaxBuilder::new(&self.ident.value_struct_name(), &self.ident.hash);
-        builder = self.add_simple_fields(builder);
+            StructSyntaxBuilder::new(self.ident.value_struct_name(), &self.ident.hash);
+        builder = self.add_simple_fields_value(builder);
+        builder = self.add_vector_fields(builder);
         builder = self.add_link_single_fields_value(builder);
         builder = self.add_link_multiple_fields_value(builder);
-        builder
+        self.add_struct_attributes(builder, DbStruct::Value)
     }
     pub fn to_serializer_builder(&self) -> StructSyntaxBuilder {
         let mut builder =
-            StructSyntaxBuilder::new(&self.ident.serializer_struct_name(), &self.ident.hash);
+            StructSyntaxBuilder::new(self.ident.serializer_struct_name(), &self.ident.hash);
         builder = self.add_simple_fields(builder);
+        builder = self.add_vector_fields(builder);
         builder = self.add_link_single_fields_serializer(builder);
         builder = self.add_link_multiple_fields_serializer(builder);
+        self.add_struct_attributes(builder, DbStruct::Serializer)
+    }
+
+    fn add_struct_attributes(
+        &self,
+        mut builder: StructSyntaxBuilder,
+        target: DbStruct,
+    ) -> StructSyntaxBuilder {
+        for (_, derive) in self.derives.iter().filter(|(t, _)| *t == target) {
+            builder.add_derive(derive);
+        }
+        for (_, attribute) in self.attributes.iter().filter(|(t, _)| *t == target) {
+            builder.add_attribute(attribute);
+        }
+        let name = &self.ident.name;
+        let doc = match target {
+            DbStruct::Main | DbStruct::Enum | DbStruct::Object | DbStruct::ValueEnum => {
+                self.doc.clone()
+            }
+            DbStruct::Id => Some(format!("Id of a [`{}`] record.", name)),
+            DbStruct::Value => Some(format!("Values used to create a [`{}`] record.", name)),
+            DbStruct::Serializer => Some(format!("Stored form of a [`{}`] record.", name)),
+        };
+        builder.with_doc(doc);
         builder
     }
 
@@ -122,20 +221,59 @@ impl DbClass {
     }
     fn add_simple_fields(&self, mut builder: StructSyntaxBuilder) -> StructSyntaxBuilder {
         for f in self.simple_fields() {
-            builder.add_field(Field::new(&f.name, &f.type_));
+            builder.add_field(
+                Field::with_decorators(&f.name, f.type_.rust_type(), f.type_.serde_decorators())
+                    .with_doc(f.doc.clone()),
+            );
+        }
+        builder
+    }
+    fn add_simple_fields_value(&self, mut builder: StructSyntaxBuilder) -> StructSyntaxBuilder {
+        for f in self.simple_fields() {
+            if f.is_readonly() {
+                continue;
+            }
+            if f.default.is_some() {
+                builder.add_field(
+                    Field::with_decorators(
+                        &f.name,
+                        format!("Option<{}>", f.type_.rust_type()),
+                        vec!["#[serde(skip_serializing_if = \"Option::is_none\")]"],
+                    )
+                    .with_doc(f.doc.clone()),
+                );
+            } else {
+                builder.add_field(
+                    Field::with_decorators(
+                        &f.name,
+                        f.type_.rust_type(),
+                        f.type_.serde_decorators(),
+                    )
+                    .with_doc(f.doc.clone()),
+                );
+            }
+        }
+        builder
+    }
+    fn add_vector_fields(&self, mut builder: StructSyntaxBuilder) -> StructSyntaxBuilder {
+        for f in self.vector_fields() {
+            builder.add_field(Field::new(&f.name, "Vec<f32>").with_doc(f.doc.clone()));
         }
         builder
     }
     fn add_link_single_fields(&self, mut builder: StructSyntaxBuilder) -> StructSyntaxBuilder {
         for f in self.link_single_fields() {
-            builder.add_field(Field::new(
-                &f.name,
-                if f.prefetch {
-                    f.ident.name
-                } else {
-                    f.ident.id_struct_name()
-                },
-            ));
+            builder.add_field(
+                Field::new(
+                    &f.name,
+                    if f.prefetch {
+                        f.ident.name
+                    } else {
+                        f.ident.id_struct_name()
+                    },
+                )
+                .with_doc(f.doc.clone()),
+            );
         }
         builder
     }
@@ -144,15 +282,19 @@ impl DbClass {
         mut builder: StructSyntaxBuilder,
     ) -> StructSyntaxBuilder {
         for f in self.link_single_fields() {
-            builder.add_field(Field::with_decorators(
-                &f.name,
-                format!(
-                    "DbLink<{}, {}>",
-                    f.ident.id_struct_name(),
-                    f.ident.value_struct_name()
-                ),
-                vec!["#[serde(serialize_with = \"db_link_to_thing\")]"],
-            ));
+            let value = if f.boxed {
+                format!("Box<{}>", f.ident.value_struct_name())
+            } else {
+                f.ident.value_struct_name()
+            };
+            builder.add_field(
+                Field::with_decorators(
+                    &f.name,
+                    format!("DbLink<{}, {}>", f.ident.id_struct_name(), value),
+                    vec!["#[serde(serialize_with = \"db_link_to_thing\")]"],
+                )
+                .with_doc(f.doc.clone()),
+            );
         }
         builder
     }
@@ -161,23 +303,26 @@ impl DbClass {
         mut builder: StructSyntaxBuilder,
     ) -> StructSyntaxBuilder {
         for f in self.link_single_fields() {
-            builder.add_field(Field::new(&f.name, "Thing"));
+            builder.add_field(Field::new(&f.name, "Thing").with_doc(f.doc.clone()));
         }
         builder
     }
     fn add_link_multiple_fields(&self, mut builder: StructSyntaxBuilder) -> StructSyntaxBuilder {
         for f in self.link_multiple_fields() {
-            builder.add_field(Field::new(
-                &f.name,
-                format!(
-                    "Vec<{}> ",
-                    if f.prefetch {
-                        f.ident.name
-                    } else {
-                        f.ident.id_struct_name()
-                    }
-                ),
-            ));
+            builder.add_field(
+                Field::new(
+                    &f.name,
+                    format!(
+                        "Vec<{}> ",
+                        if f.prefetch {
+                            f.ident.name
+                        } else {
+                            f.ident.id_struct_name()
+                        }
+                    ),
+                )
+                .with_doc(f.doc.clone()),
+            );
         }
         builder
     }
@@ -186,15 +331,18 @@ impl DbClass {
         mut builder: StructSyntaxBuilder,
     ) -> StructSyntaxBuilder {
         for f in self.link_multiple_fields() {
-            builder.add_field(Field::with_decorators(
-                &f.name,
-                format!(
-                    "DbLink<Vec<{}>, Vec<{}>>",
-                    f.ident.id_struct_name(),
-                    f.ident.value_struct_name()
-                ),
-                vec!["#[serde(serialize_with = \"db_link_to_vec_thing\")]"],
-            ));
+            builder.add_field(
+                Field::with_decorators(
+                    &f.name,
+                    format!(
+                        "DbLink<Vec<{}>, Vec<{}>>",
+                        f.ident.id_struct_name(),
+                        f.ident.value_struct_name()
+                    ),
+                    vec!["#[serde(serialize_with = \"db_link_to_vec_thing\")]"],
+                )
+                .with_doc(f.doc.clone()),
+            );
         }
         builder
     }
@@ -203,7 +351,7 @@ impl DbClass {
         mut builder: StructSyntaxBuilder,
     ) -> StructSyntaxBuilder {
         for f in self.link_multiple_fields() {
-            builder.add_field(Field::new(&f.name, "Vec<Thing>"));
+            builder.add_field(Field::new(&f.name, "Vec<Thing>").with_doc(f.doc.clone()));
         }
         builder
     }
@@ -0,0 +1,421 @@
+use std::fs;
+use std::path::PathBuf;
+
+use db_engine::{
+    build, db_class::DbClass, db_class::DbClassIdentifier, db_class::DbStruct,
+    db_field::DbClassLinkMultiple as LnM, db_field::DbClassLinkSingle as LnS,
+    db_field::DbClassSimpleField as SF, db_field::DbClassVectorField, db_field::DbFieldDefault,
+    db_field::DbFieldRule, db_field::DbFieldType, db_field::DbVectorDistance,
+    db_manager::DbManager, db_object::DbObject, db_value_enum::DbValueEnum,
+};
+
+fn prefetch() -> DbManager {
+    let mut mng = DbManager::new();
+    let owner =
+        mng.add_class(DbClass::with_name("Owner").add_field(SF::new("name", DbFieldType::String)));
+    let car = mng.add_class(
+        DbClass::with_name("Car")
+            .add_field(SF::new("model", DbFieldType::String))
+            .add_field(LnS::new_prefetch("owner", &owner)),
+    );
+    mng.add_class(
+        DbClass::with_name("Garage")
+            .add_field(LnM::new_prefetch("cars", &car))
+            .add_field(LnM::new("visitors", &owner)),
+    );
+    mng
+}
+
+fn enums() -> DbManager {
+    let mut mng = DbManager::new();
+    let animal =
+        mng.add_class(DbClass::with_name("Animal").add_field(SF::new("name", DbFieldType::String)));
+    let dog =
+        mng.add_class(DbClass::with_name("Dog").add_field(SF::new("good_boy", DbFieldType::Bool)));
+    let cat = mng.add_class(
+        DbClass::with_name("Cat")
+            .add_field(SF::new("lives", DbFieldType::U8))
+            .with_soft_delete(),
+    );
+    mng.add_enum("AnimalEnum", &animal, vec![&dog, &cat]);
+    mng.add_enum_attribute("AnimalEnum", "#[non_exhaustive]");
+    mng
+}
+
+fn extensions() -> DbManager {
+    let mut mng = DbManager::new();
+    let person =
+        mng.add_class(DbClass::with_name("Person").add_field(SF::new("name", DbFieldType::String)));
+    let user =
+        mng.add_class(DbClass::with_name("User").add_field(SF::new("email", DbFieldType::String)));
+    let guest =
+        mng.add_class(DbClass::with_name("Guest").add_field(SF::new("nick", DbFieldType::String)));
+    mng.add_enum("PersonEnum", &person, vec![&user, &guest]);
+    mng.add_extension(&person, "PersonEnum", &user);
+    mng.add_extension(&person, "PersonEnum", &guest);
+    mng
+}
+
+fn cycles() -> DbManager {
+    let mut mng = DbManager::new();
+    let author = DbClassIdentifier::new("Author".to_string());
+    let book = DbClassIdentifier::new("Book".to_string());
+    mng.add_class(
+        DbClass::new(author.clone())
+            .add_field(SF::new("name", DbFieldType::String))
+            .add_field(LnM::new("books", &book))
+            .add_field(LnS::new("mentor", &author)),
+    );
+    mng.add_class(
+        DbClass::new(book)
+            .add_field(SF::new("title", DbFieldType::String))
+            .add_field(LnS::new("author", &author)),
+    );
+    mng
+}
+
+fn optional_fields() -> DbManager {
+    let mut mng = DbManager::new();
+    let status = mng.add_value_enum(
+        DbValueEnum::with_name("Status")
+            .add_variant_with_value("Open", "open")
+            .add_variant_with_value("Closed", "closed"),
+    );
+    let priority = mng.add_value_enum(
+        DbValueEnum::with_name("Priority")
+            .add_variant_with_int("Low", 1)
+            .add_variant_with_int("High", 10)
+            .with_derive("PartialOrd")
+            .with_derive("Ord"),
+    );
+    let address = mng.add_object(
+        DbObject::with_name("Address")
+            .add_field(SF::new("street", DbFieldType::String))
+            .add_field(SF::new("zip", DbFieldType::option(DbFieldType::String)))
+            .with_derive("PartialEq"),
+    );
+    mng.add_class(
+        DbClass::with_name("Shop")
+            .add_field(SF::new("name", DbFieldType::String))
+            .add_field(SF::new("rating", DbFieldType::option(DbFieldType::F32)))
+            .add_field(SF::new(
+                "address",
+                DbFieldType::option(DbFieldType::embedded(&address)),
+            ))
+            .add_field(SF::new(
+                "status",
+                DbFieldType::option(DbFieldType::value_enum(&status)),
+            ))
+            .add_field(SF::new(
+                "tags",
+                DbFieldType::option(DbFieldType::array(DbFieldType::String)),
+            ))
+            .add_field(SF::new("priority", DbFieldType::value_enum(&priority)))
+            .add_field(SF::new("revenue", DbFieldType::option(DbFieldType::I128))),
+    );
+    mng
+}
+
+fn rules() -> DbManager {
+    let mut mng = DbManager::new();
+    mng.add_class(
+        DbClass::with_name("Account")
+            .add_field(SF::with_rules(
+                "age",
+                DbFieldType::I32,
+                vec![
+                    DbFieldRule::Min("-5".to_string()),
+                    DbFieldRule::Max("150".to_string()),
+                ],
+            ))
+            .add_field(SF::with_rules(
+                "score",
+                DbFieldType::F64,
+                vec![
+                    DbFieldRule::Min("0".to_string()),
+                    DbFieldRule::Max("1.5".to_string()),
+                    DbFieldRule::OneOf(vec!["0".to_string(), "0.5".to_string()]),
+                ],
+            ))
+            .add_field(SF::with_rules(
+                "name",
+                DbFieldType::String,
+                vec![
+                    DbFieldRule::MinLength(2),
+                    DbFieldRule::MaxLength(20),
+                    DbFieldRule::Regex("^[a-z]+$".to_string()),
+                    DbFieldRule::OneOf(vec!["\"ann\"".to_string(), "\"bob\"".to_string()]),
+                    DbFieldRule::Custom("no_spaces".to_string()),
+                ],
+            ))
+            .add_field(SF::with_rules(
+                "grade",
+                DbFieldType::Char,
+                vec![DbFieldRule::OneOf(vec![
+                    "'a'".to_string(),
+                    "'b'".to_string(),
+                ])],
+            ))
+            .add_field(SF::with_rules(
+                "active",
+                DbFieldType::Bool,
+                vec![DbFieldRule::OneOf(vec!["true".to_string()])],
+            ))
+            .add_field(
+                SF {
+                    name: "level".to_string(),
+                    doc: None,
+                    type_: DbFieldType::U8,
+                    rules: vec![DbFieldRule::Min("1".to_string())],
+                    default: Some(DbFieldDefault::Literal("1".to_string())),
+                    value: None,
+                    readonly: false,
+                }
+                .into(),
+            ),
+    );
+    mng
+}
+
+fn keywords() -> DbManager {
+    let mut mng = DbManager::new();
+    let place = mng.add_object(
+        DbObject::with_name("Place")
+            .add_field(SF::new("where", DbFieldType::String))
+            .add_field(SF::new("loop", DbFieldType::Bool)),
+    );
+    let host =
+        mng.add_class(DbClass::with_name("Host").add_field(SF::new("struct", DbFieldType::String)));
+    mng.add_class(
+        DbClass::with_name("Event")
+            .add_field(SF::with_rules(
+                "match",
+                DbFieldType::String,
+                vec![DbFieldRule::MinLength(1)],
+            ))
+            .add_field(SF::with_default(
+                "async",
+                DbFieldType::Bool,
+                DbFieldDefault::Literal("false".to_string()),
+            ))
+            .add_field(SF::new("in", DbFieldType::embedded(&place)))
+            .add_field(LnS::new_prefetch("impl", &host))
+            .add_field(LnM::new("use", &host)),
+    );
+    mng
+}
+
+// Uses every class option at once, so their generated code is checked side by side.
+fn all_options() -> DbManager {
+    let mut mng = DbManager::new();
+    mng.add_derive(DbStruct::Id, "PartialEq");
+    let author = mng.add_class(
+        DbClass::with_name("Author")
+            .with_doc("Writes articles.")
+            .with_derive(DbStruct::Main, "PartialEq")
+            .add_field(SF::new("name", DbFieldType::String).with_doc("Full name.")),
+    );
+    mng.add_class(
+        DbClass::with_name("Article")
+            .with_doc("A published text.")
+            .with_derive(DbStruct::Id, "Eq")
+            .with_attribute(DbStruct::Main, "#[must_use]")
+            .with_db_asserts()
+            .with_timestamps()
+            .with_soft_delete()
+            .with_version()
+            .with_history()
+            .add_field(
+                SF::with_rules(
+                    "title",
+                    DbFieldType::String,
+                    vec![
+                        DbFieldRule::MinLength(1),
+                        DbFieldRule::MaxLength(80),
+                        DbFieldRule::Regex("^[A-Z]".to_string()),
+                        DbFieldRule::Custom("no_spaces".to_string()),
+                    ],
+                )
+                .with_doc("Shown as the heading."),
+            )
+            .add_field(
+                SF {
+                    name: "views".to_string(),
+                    doc: None,
+                    type_: DbFieldType::U32,
+                    rules: vec![DbFieldRule::Max("1000000".to_string())],
+                    default: Some(DbFieldDefault::Literal("0".to_string())),
+                    value: None,
+                    readonly: false,
+                }
+                .into(),
+            )
+            .add_field(SF::with_default(
+                "rating",
+                DbFieldType::option(DbFieldType::U8),
+                DbFieldDefault::Rust("Some(3)".to_string()),
+            ))
+            .add_field(SF::with_default(
+                "published",
+                DbFieldType::Bool,
+                DbFieldDefault::Surql("false".to_string()),
+            ))
+            .add_field(
+                SF {
+                    name: "slug".to_string(),
+                    doc: Some("Derived from the title.".to_string()),
+                    type_: DbFieldType::String,
+                    rules: vec![],
+                    default: None,
+                    value: Some("string::lowercase(title)".to_string()),
+                    readonly: true,
+                }
+                .into(),
+            )
+            .add_field(
+                DbClassVectorField::new("embedding", 3, DbVectorDistance::Cosine)
+                    .with_doc("Text embedding."),
+            )
+            .add_field(LnS::new_prefetch("author", &author).with_doc("Main author."))
+            .add_field(LnM::new("reviewers", &author)),
+    );
+    mng
+}
+
+// Custom rules of the schemas above call these.
+const RULE_HELPERS: &str = r#"
+fn no_spaces(value: &str) -> Result<(), String> {
+    if value.contains(' ') {
+        return Err("must not contain spaces".to_string());
+    }
+    Ok(())
+}
+"#;
+
+fn schemas() -> Vec<(&'static str, DbManager)> {
+    vec![
+        ("prefetch", prefetch()),
+        ("enums", enums()),
+        ("extensions", extensions()),
+        ("cycles", cycles()),
+        ("optional_fields", optional_fields()),
+        ("rules", rules()),
+        ("keywords", keywords()),
+        ("all_options", all_options()),
+    ]
+}
+
+#[test]
+fn generated_code_snapshots() {
+    for (name, schema) in schemas() {
+        let code = build::to_code(schema).unwrap();
+        insta::assert_snapshot!(name, code);
+    }
+}
+
+#[test]
+fn generated_code_compiles() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+    fs::create_dir_all(&dir).unwrap();
+    let t = trybuild::TestCases::new();
+    for (name, schema) in schemas() {
+        let code = build::to_code(schema).unwrap();
+        let path = dir.join(format!("{}.rs", name));
+        fs::write(
+            &path,
+            format!(
+                "#![allow(dead_code)]\n{}\n{}\nfn main() {{}}\n",
+                code, RULE_HELPERS
+            ),
+        )
+        .unwrap();
+        t.pass(path);
+    }
+}
+
+#[test]
+fn generated_modules_replace_stale_files() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("modules");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let stale = format!("{}\npub struct RemovedClass;\n", build::GENERATED_HEADER);
+    fs::write(dir.join("removed_class.rs"), stale).unwrap();
+    fs::write(dir.join("helpers.rs"), "pub fn helper() {}\n").unwrap();
+    fs::write(dir.join("notes.txt"), "").unwrap();
+    build::try_generate_modules(prefetch(), &dir).unwrap();
+    let mut files = fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    files.sort();
+    assert_eq!(
+        files,
+        [
+            "car.rs",
+            "garage.rs",
+            "helpers.rs",
+            "mod.rs",
+            "notes.txt",
+            "owner.rs"
+        ]
+    );
+}
+
+#[test]
+fn module_names_are_checked() {
+    let mut mng = DbManager::new();
+    mng.add_class(DbClass::with_name("Mod"));
+    mng.add_class(DbClass::with_name("Type"));
+    mng.add_class(DbClass::with_name("FooBar"));
+    mng.add_class(DbClass::with_name("Foo_Bar"));
+    let errors = mng.validate_modules().unwrap_err();
+    let messages = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "Mod: `mod` cannot be used as a module name",
+            "Type: `type` cannot be used as a module name",
+            "Foo_Bar: module `foo_bar` is generated for more than one type",
+        ]
+    );
+    assert!(build::try_generate_modules(mng, PathBuf::from("unused")).is_err());
+}
+
+#[test]
+fn derived_names_and_literals_are_checked() {
+    let mut mng = DbManager::new();
+    mng.add_class(DbClass::with_name("Person"));
+    mng.add_class(DbClass::with_name("PersonId"));
+    let owner = mng.add_class(
+        DbClass::with_name("Owner")
+            .add_field(SF::with_default(
+                "name",
+                DbFieldType::String,
+                DbFieldDefault::Literal("unquoted".to_string()),
+            ))
+            .add_field(SF::with_rules(
+                "age",
+                DbFieldType::I32,
+                vec![DbFieldRule::OneOf(vec![
+                    "-1".to_string(),
+                    "1 +".to_string(),
+                ])],
+            )),
+    );
+    mng.add_object(DbObject::with_name("Address").add_field(LnS::new("owner", &owner)));
+    let messages = mng
+        .validate()
+        .unwrap_err()
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "Person: generated type `PersonId` collides with another type",
+            "Address.owner: embedded objects only support simple fields",
+            "Owner.name: `unquoted` is not a valid literal",
+            "Owner.age: `1 +` is not a valid literal",
+        ]
+    );
+}
@@ -0,0 +1,216 @@
+use std::fs;
+use std::path::PathBuf;
+
+use db_engine::{
+    build, db_class::DbClass, db_field::DbClassLinkMultiple as LnM,
+    db_field::DbClassLinkSingle as LnS, db_field::DbClassSimpleField as SF,
+    db_field::DbClassVectorField, db_field::DbFieldType, db_field::DbVectorDistance,
+    db_manager::DbManager,
+};
+
+fn schema() -> DbManager {
+    let mut mng = DbManager::new();
+    let owner = mng.add_class(
+        DbClass::with_name("Owner")
+            .add_field(SF::new("name", DbFieldType::String))
+            .add_field(SF::new("age", DbFieldType::U16))
+            .with_history(),
+    );
+    let car = mng.add_class(
+        DbClass::with_name("Car")
+            .add_field(SF::new("model", DbFieldType::String))
+            .add_field(LnS::new_prefetch("owner", &owner)),
+    );
+    mng.add_class(DbClass::with_name("Garage").add_field(LnM::new_prefetch("cars", &car)));
+    mng.add_class(
+        DbClass::with_name("Spot")
+            .add_field(SF::new("name", DbFieldType::String))
+            .add_field(DbClassVectorField::new(
+                "position",
+                2,
+                DbVectorDistance::Euclidean,
+            ))
+            .with_soft_delete(),
+    );
+    let animal =
+        mng.add_class(DbClass::with_name("Animal").add_field(SF::new("name", DbFieldType::String)));
+    let dog = mng.add_class(
+        DbClass::with_name("Dog")
+            .add_field(SF::new("name", DbFieldType::String))
+            .add_field(SF::new("good_boy", DbFieldType::Bool)),
+    );
+    let cat = mng.add_class(
+        DbClass::with_name("Cat")
+            .add_field(SF::new("name", DbFieldType::String))
+            .add_field(SF::new("lives", DbFieldType::U8)),
+    );
+    mng.add_enum("AnimalEnum", &animal, vec![&dog, &cat]);
+    mng
+}
+
+const MAIN: &str = r#"
+#[tokio::main]
+async fn main() {
+    let endpoint = std::env::var("SURREAL_ENDPOINT").unwrap_or_else(|_| "mem://".to_string());
+    let db = surrealdb::engine::any::connect(endpoint).await.unwrap();
+    db.use_ns("e2e").use_db("e2e").await.unwrap();
+    db_define(&db).await.unwrap();
+
+    let owner_id = ValueOwner {
+        name: "Jan".to_string(),
+        age: 30,
+    }
+    .db_create(&db)
+    .await
+    .unwrap();
+    let mut owner = owner_id.db_get(&db).await.unwrap().unwrap();
+    assert_eq!(owner.name, "Jan");
+    assert_eq!(owner.age, 30);
+
+    owner.age = 31;
+    owner.db_update(&db).await.unwrap();
+    assert_eq!(owner_id.db_get(&db).await.unwrap().unwrap().age, 31);
+
+    let garage = ValueGarage {
+        cars: DbLink::New(vec![
+            ValueCar {
+                model: "Fiat".to_string(),
+                owner: DbLink::Existing(owner_id.clone()),
+            },
+            ValueCar {
+                model: "Skoda".to_string(),
+                owner: DbLink::New(ValueOwner {
+                    name: "Anna".to_string(),
+                    age: 25,
+                }),
+            },
+        ]),
+    }
+    .db_create_get(&db)
+    .await
+    .unwrap();
+    let mut models: Vec<_> = garage.cars.iter().map(|c| c.model.as_str()).collect();
+    models.sort();
+    assert_eq!(models, ["Fiat", "Skoda"]);
+    let fiat = garage.cars.iter().find(|c| c.model == "Fiat").unwrap();
+    assert_eq!(fiat.owner.id, owner_id.id);
+    assert_eq!(fiat.owner.age, 31);
+    let skoda = garage.cars.iter().find(|c| c.model == "Skoda").unwrap();
+    assert_eq!(skoda.owner.name, "Anna");
+
+    let history = owner_id.db_history(&db).await.unwrap();
+    let events: Vec<_> = history.iter().map(|h| h.event.as_str()).collect();
+    assert_eq!(events, ["CREATE", "UPDATE"]);
+    assert_eq!(history[1].value.as_ref().unwrap().age, 31);
+    let created = owner_id.db_get_as_of(&db, history[0].at.clone()).await.unwrap();
+    assert_eq!(created.unwrap().age, 30);
+
+    let by_name = DbListQuery::offset(0, 10).order_by(OwnerOrderBy::Name);
+    let page = Owner::db_list(&db, by_name.with_total()).await.unwrap();
+    let names: Vec<_> = page.items.iter().map(|o| o.name.as_str()).collect();
+    assert_eq!(names, ["Anna", "Jan"]);
+    assert_eq!((page.total, page.has_more), (Some(2), false));
+    let page = Owner::db_list(&db, DbListQuery::offset(1, 1).order_by(OwnerOrderBy::Name))
+        .await
+        .unwrap();
+    assert_eq!(page.items[0].name, "Jan");
+
+    let first = DbListQuery::cursor(None, 1).order_by(OwnerOrderBy::Name);
+    let page = Owner::db_list(&db, first).await.unwrap();
+    assert_eq!((page.items[0].name.as_str(), page.has_more), ("Anna", true));
+    let next = DbListQuery::cursor(page.next_cursor, 1).order_by(OwnerOrderBy::Name);
+    let page = Owner::db_list(&db, next).await.unwrap();
+    assert_eq!(page.items[0].name, "Jan");
+    assert_eq!(page.next_cursor, None);
+
+    let mut streamed: Vec<_> = Owner::db_stream(&db, 1)
+        .map_ok(|o| o.name)
+        .try_collect()
+        .await
+        .unwrap();
+    streamed.sort();
+    assert_eq!(streamed, ["Anna", "Jan"]);
+
+    let total = Owner::db_aggregate()
+        .metric(OwnerMetric::Age)
+        .fetch(&db)
+        .await
+        .unwrap();
+    assert_eq!(total.len(), 1);
+    assert_eq!(total[0].count, 2);
+    assert_eq!((total[0].sum, total[0].min, total[0].max), (Some(56.0), Some(25.0), Some(31.0)));
+    let mut groups: Vec<_> = Owner::db_aggregate()
+        .group_by(OwnerGroupBy::Name)
+        .fetch(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| match row.key {
+            Some(OwnerGroupKey::Name(name)) => (name, row.count),
+            key => panic!("unexpected group {:?}", key),
+        })
+        .collect();
+    groups.sort();
+    assert_eq!(groups, [("Anna".to_string(), 1), ("Jan".to_string(), 1)]);
+
+    let garage_id = GarageId::from(garage);
+    garage_id.db_delete(&db).await.unwrap();
+    assert!(garage_id.db_get(&db).await.unwrap().is_none());
+
+    let mut spots = vec![];
+    for i in 0..5 {
+        let spot = ValueSpot {
+            name: format!("spot {}", i),
+            position: vec![i as f32, 0.0],
+        };
+        spots.push(spot.db_create(&db).await.unwrap());
+    }
+    spots[0].db_delete(&db).await.unwrap().unwrap();
+    spots[1].db_delete(&db).await.unwrap().unwrap();
+    let nearest = Spot::db_knn(&db, &[0.0, 0.0], 2).await.unwrap();
+    let nearest: Vec<_> = nearest.iter().map(|(s, d)| (s.name.as_str(), *d)).collect();
+    assert_eq!(nearest, [("spot 2", 2.0), ("spot 3", 3.0)]);
+
+    ValueDog {
+        name: "Rex".to_string(),
+        good_boy: true,
+    }
+    .db_create(&db)
+    .await
+    .unwrap();
+    ValueCat {
+        name: "Tom".to_string(),
+        lives: 9,
+    }
+    .db_create(&db)
+    .await
+    .unwrap();
+    ValueAnimal {
+        name: "Bird".to_string(),
+    }
+    .db_create(&db)
+    .await
+    .unwrap();
+    let by_name = DbListQuery::offset(0, 10).order_by(AnimalEnumOrderBy::Name);
+    let page = AnimalEnum::db_list(&db, by_name.with_total()).await.unwrap();
+    assert_eq!(page.total, Some(3));
+    assert!(matches!(
+        page.items.as_slice(),
+        [
+            AnimalEnum::Animal(Animal { name: bird, .. }),
+            AnimalEnum::Dog(Dog { good_boy: true, .. }),
+            AnimalEnum::Cat(Cat { lives: 9, .. }),
+        ] if bird == "Bird"
+    ));
+}
+"#;
+
+#[test]
+fn generated_code_round_trip() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("e2e");
+    fs::create_dir_all(&dir).unwrap();
+    let code = build::to_code(schema()).unwrap();
+    let path = dir.join("round_trip.rs");
+    fs::write(&path, format!("#![allow(dead_code)]\n{}\n{}", code, MAIN)).unwrap();
+    trybuild::TestCases::new().pass(path);
+}
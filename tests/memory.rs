@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::PathBuf;
+
+use db_engine::{
+    build, db_class::DbClass, db_field::DbClassLinkMultiple as LnM,
+    db_field::DbClassLinkSingle as LnS, db_field::DbClassSimpleField as SF, db_field::DbFieldType,
+    db_manager::DbManager,
+};
+
+fn schema() -> DbManager {
+    let mut mng = DbManager::new();
+    let owner = mng.add_class(
+        DbClass::with_name("Owner")
+            .add_field(SF::new("name", DbFieldType::String))
+            .add_field(SF::new("age", DbFieldType::U16))
+            .with_soft_delete(),
+    );
+    let car = mng.add_class(
+        DbClass::with_name("Car")
+            .add_field(SF::new("model", DbFieldType::String))
+            .add_field(LnS::new_prefetch("owner", &owner))
+            .with_timestamps()
+            .with_version(),
+    );
+    mng.add_class(
+        DbClass::with_name("Garage")
+            .add_field(LnM::new_prefetch("cars", &car))
+            .add_field(LnM::new("visitors", &owner)),
+    );
+    mng.add_class(
+        DbClass::with_name("Ticket")
+            .add_field(SF::new("title", DbFieldType::String))
+            .add_field(SF::computed("code", DbFieldType::String, "rand::uuid()")),
+    );
+    mng
+}
+
+const MAIN: &str = r#"
+#[tokio::main]
+async fn main() {
+    let db = DbMemory::new();
+    db.insert(
+        OwnerId::class_hash(),
+        "jan",
+        ValueOwner {
+            name: "Jan".to_string(),
+            age: 30,
+        },
+    )
+    .unwrap();
+    let jan = OwnerId { id: "jan".to_string() };
+
+    let mut owner = jan.db_get(&db).await.unwrap().unwrap();
+    assert_eq!(owner.name, "Jan");
+    owner.age = 31;
+    owner.db_update(&db).await.unwrap().unwrap();
+    assert_eq!(db.record(&OwnerId::class_hash(), "jan").unwrap()["age"], 31);
+    let merged = owner
+        .db_merge(&db, serde_json::json!({ "name": "Jan K." }))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((merged.name.as_str(), merged.age), ("Jan K.", 31));
+
+    let garage = ValueGarage {
+        cars: DbLink::New(vec![ValueCar {
+            model: "Skoda".to_string(),
+            owner: DbLink::New(ValueOwner {
+                name: "Anna".to_string(),
+                age: 25,
+            }),
+        }]),
+        visitors: DbLink::Existing(vec![jan.clone()]),
+    }
+    .db_create_get(&db)
+    .await
+    .unwrap();
+    assert_eq!(garage.cars[0].model, "Skoda");
+    assert_eq!(garage.cars[0].owner.name, "Anna");
+    assert_eq!(garage.visitors[0].id, "jan");
+    assert_eq!(db.records(&OwnerId::class_hash()).len(), 2);
+    assert_eq!(db.records(&CarId::class_hash()).len(), 1);
+
+    jan.db_delete(&db).await.unwrap().unwrap();
+    assert!(jan.db_get(&db).await.unwrap().is_none());
+    assert!(jan.with_deleted().db_get(&db).await.unwrap().is_some());
+    assert!(owner.db_update(&db).await.unwrap().is_none());
+    jan.db_restore(&db).await.unwrap().unwrap();
+    assert_eq!(jan.db_get(&db).await.unwrap().unwrap().age, 31);
+
+    let mut car = garage.cars[0].clone();
+    assert_eq!(car.version, 1);
+    assert_eq!(car.created_at, car.updated_at);
+    let stale = car.clone();
+    car.model = "Octavia".to_string();
+    car.db_update(&db).await.unwrap().unwrap();
+    assert_eq!(car.version, 2);
+    let updated = CarId::from(car.clone()).db_get(&db).await.unwrap().unwrap();
+    assert_eq!((updated.model.as_str(), updated.version), ("Octavia", 2));
+    assert_eq!(updated.created_at, stale.created_at);
+    assert!(updated.updated_at >= stale.updated_at);
+    let mut stale = stale;
+    assert!(matches!(stale.db_update(&db).await, Err(DbError::Conflict(_))));
+
+    assert!(matches!(
+        ValueTicket {
+            title: "Oil change".to_string(),
+        }
+        .db_create(&db)
+        .await,
+        Err(DbError::Unsupported(_))
+    ));
+
+    let car = CarId::from(car);
+    let anna = OwnerId::from(garage.cars[0].owner.clone());
+    anna.db_delete(&db).await.unwrap().unwrap();
+    assert_eq!(car.db_get(&db).await.unwrap().unwrap().owner.name, "Anna");
+
+    car.db_delete(&db).await.unwrap().unwrap();
+    assert!(db.records(&CarId::class_hash()).is_empty());
+}
+"#;
+
+#[test]
+fn generated_code_against_memory_backend() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("memory");
+    fs::create_dir_all(&dir).unwrap();
+    let code = build::to_code(schema()).unwrap();
+    let path = dir.join("memory.rs");
+    fs::write(&path, format!("#![allow(dead_code)]\n{}\n{}", code, MAIN)).unwrap();
+    trybuild::TestCases::new().pass(path);
+}